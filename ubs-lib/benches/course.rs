@@ -0,0 +1,78 @@
+use std::{hint::black_box, str::FromStr};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use ubs_lib::Course;
+
+// First, middle, and last courses in `courses.csv`, along with an unknown course
+const NAMES: [&str; 4] = ["JPN101LEC", "cse 474 lec", "CSE-115-LLR", "XYZ999"];
+// Same as above, along with an unknown course
+const IDS: [&str; 4] = ["004097", "017768", "004544", "999999"];
+
+fn parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Course::from_str");
+    group.bench_function("phf", |b| {
+        b.iter(|| {
+            for name in NAMES {
+                let _ = black_box(Course::from_str(black_box(name)));
+            }
+        })
+    });
+    // Comparing the normalized name against every course, as the previous `match` did
+    let names: Vec<_> = Course::ALL
+        .into_iter()
+        .map(|course| (normalize(&course.to_string()), course))
+        .collect();
+    group.bench_function("linear", |b| {
+        b.iter(|| {
+            for name in NAMES {
+                let name = normalize(black_box(name));
+                black_box(
+                    names
+                        .iter()
+                        .find(|(candidate, _)| *candidate == name)
+                        .map(|(_, course)| (*course).clone()),
+                );
+            }
+        })
+    });
+    group.finish();
+}
+
+fn from_id(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Course::from_id");
+    group.bench_function("phf", |b| {
+        b.iter(|| {
+            for id in IDS {
+                black_box(Course::from_id(black_box(id)));
+            }
+        })
+    });
+    // Linear search over every course, as was previously necessary. `Course::ALL` is a constant,
+    // thus it's bound outside the loop so that it isn't copied on every iteration.
+    let courses = Course::ALL;
+    group.bench_function("linear", |b| {
+        b.iter(|| {
+            for id in IDS {
+                black_box(
+                    courses
+                        .iter()
+                        .find(|course| course.id() == black_box(id))
+                        .cloned()
+                        .unwrap_or_else(|| Course::Raw(id.to_owned())),
+                );
+            }
+        })
+    });
+    group.finish();
+}
+
+/// Normalize a course name the same as `Course::from_str`, such as `cse 115-llr` to `CSE115LLR`.
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| !c.is_whitespace() && *c != '-' && *c != '_')
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+criterion_group!(benches, parse, from_id);
+criterion_main!(benches);
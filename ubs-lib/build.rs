@@ -1,17 +1,30 @@
 use convert_case::{Case, Casing};
-use csv::Reader;
+use csv::ReaderBuilder;
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::quote;
 use std::{collections::HashMap, env, fs, io, path::Path};
 
+#[path = "src/infer.rs"]
+mod infer;
+
+// Columns are the course id, the career id (inferred from the name if empty), the name, the title,
+// the credit hours (e.g. `4` or `1-3`), the pathways separated by semicolons, and the department.
+// The career id is one of `UGRD`, `GRAD`, `LAW`, `SDM`, `MED`, or `PHRM`. Every column after the
+// name is optional and may be omitted or left empty if unknown. Pathways are filled in from the
+// path-finder topics export by `ubs catalog import`, though it hasn't been run against the
+// catalog yet. The title, credit hours, and department are yet to have a source, thus they're
+// deferred and empty for nearly every course.
 const COURSES_PATH: &str = "data/courses.csv";
 
-// in case more information is added to each course (ex: is it a pathway?)
 #[derive(Debug, Clone)]
 struct Course {
     // id: String,
     career: String,
     name: String,
+    title: Option<String>,
+    credits: Option<(f32, f32)>,
+    pathways: Vec<String>,
+    department: Option<String>,
 }
 
 // TODO: split this function into many smaller functions,
@@ -29,16 +42,91 @@ fn generate_tokens(courses: HashMap<String, Course>) -> io::Result<TokenStream>
     let names2 = names1.clone();
     let names3 = names1.clone();
     let names4 = names1.clone();
-    let names5 = courses
-        .values()
-        .map(|course| course.name.to_ascii_uppercase());
-    let names6 = names1.clone();
+    let names7 = names1.clone();
+    let names8 = names1.clone();
+    let names9 = names1.clone();
+    let names10 = names1.clone();
+    let names11 = names1.clone();
+    let names12 = names1.clone();
+    let names13 = names1.clone();
+
+    let parts = courses.values().map(|course| {
+        infer::split_course_name(&course.name)
+            .unwrap_or_else(|| panic!("invalid course name `{}`", course.name))
+    });
+    let subjects = parts.clone().map(|(subject, _, _)| subject);
+    let catalog_numbers = parts.clone().map(|(_, catalog_number, _)| catalog_number);
+    let components = parts.clone().map(|(_, _, component)| match component {
+        "" => quote!(None),
+        component => quote!(Some(#component)),
+    });
+
+    let titles = courses.values().map(|course| match &course.title {
+        Some(title) => quote!(Some(#title)),
+        None => quote!(None),
+    });
+    let credits = courses.values().map(|course| match course.credits {
+        Some((min, max)) => quote!(Some(Credits { min: #min, max: #max })),
+        None => quote!(None),
+    });
+    let pathways = courses.values().map(|course| {
+        let pathways = &course.pathways;
+        quote!(&[#(#pathways),*])
+    });
+    let departments = courses.values().map(|course| match &course.department {
+        Some(department) => quote!(Some(#department)),
+        None => quote!(None),
+    });
+
+    // Courses specified without a component default to the preferred component, shared with
+    // `Catalog`.
+    let mut defaults: HashMap<String, (&str, &Course)> = HashMap::new();
+    for ((subject, catalog_number, component), course) in parts.zip(courses.values()) {
+        if component.is_empty() {
+            continue;
+        }
+
+        let key = infer::normalize_course_name(&format!("{subject}{catalog_number}"));
+        match defaults.get(&key) {
+            Some((default, _)) if !infer::is_preferred_component(component, default) => {}
+            _ => {
+                defaults.insert(key, (component, course));
+            }
+        }
+    }
+
+    // Maps of normalized names, including those without a component, and ids to courses
+    let variant = |course: &Course| format!("Course::{}", course.name.to_case(Case::Pascal));
+    let mut names_map = phf_codegen::Map::new();
+    for course in courses.values() {
+        names_map.entry(infer::normalize_course_name(&course.name), &variant(course));
+    }
+    for (key, (_, course)) in &defaults {
+        // A course without a component takes precedence
+        if !courses
+            .values()
+            .any(|course| infer::normalize_course_name(&course.name) == *key)
+        {
+            names_map.entry(key.clone(), &variant(course));
+        }
+    }
+    let mut ids_map = phf_codegen::Map::new();
+    for (id, course) in &courses {
+        ids_map.entry(id.as_str(), &variant(course));
+    }
+    let names_map = syn::parse_str::<syn::Expr>(&names_map.build().to_string()).unwrap();
+    let ids_map = syn::parse_str::<syn::Expr>(&ids_map.build().to_string()).unwrap();
 
     let careers = courses
         .values()
         .map(|course| match course.career.as_str() {
             "UGRD" => "Undergraduate",
-            _ => "",
+            "GRAD" => "Graduate",
+            "LAW" => "Law",
+            "SDM" => "DentalMedicine",
+            "MED" => "Medicine",
+            "PHRM" => "Pharmacy",
+            career => panic!("unknown career `{career}` for course `{}`", course.name),
         })
         // TODO: use format_ident!
         .map(|career| Ident::new(career, Span::call_site()));
@@ -46,7 +134,10 @@ fn generate_tokens(courses: HashMap<String, Course>) -> io::Result<TokenStream>
     // TODO: add this as module-level doc
     // Mappings of course/semester/career to internal ids.
     Ok(quote!(
-        use std::str::FromStr;
+        use std::{
+            hash::{Hash, Hasher},
+            str::FromStr,
+        };
 
         use thiserror::Error;
 
@@ -76,6 +167,15 @@ fn generate_tokens(courses: HashMap<String, Course>) -> io::Result<TokenStream>
                 }
             }
 
+            /// Construct a [`Course`](Course) from its internal id, or
+            /// [`Course::Raw`](Course::Raw) if it isn't in the catalog.
+            pub fn from_id(id: &str) -> Course {
+                COURSES_BY_ID
+                    .get(id)
+                    .cloned()
+                    .unwrap_or_else(|| Course::Raw(id.to_owned()))
+            }
+
             /// Internal id of the course.
             pub fn id(&self) -> &str {
                 match self {
@@ -83,69 +183,152 @@ fn generate_tokens(courses: HashMap<String, Course>) -> io::Result<TokenStream>
                     Course::Raw(id) => id,
                 }
             }
+
+            /// Subject of the course, such as `CSE`.
+            ///
+            /// Returns `None` for [`Course::Raw`](Course::Raw).
+            pub fn subject(&self) -> Option<&str> {
+                match self {
+                    #(Course::#names7 => Some(#subjects),)*
+                    Course::Raw(_) => None,
+                }
+            }
+
+            /// Catalog number of the course, such as `115`.
+            ///
+            /// Returns `None` for [`Course::Raw`](Course::Raw).
+            pub fn catalog_number(&self) -> Option<&str> {
+                match self {
+                    #(Course::#names8 => Some(#catalog_numbers),)*
+                    Course::Raw(_) => None,
+                }
+            }
+
+            /// Component of the course, such as `LEC`, `LLR`, or `SEM`.
+            ///
+            /// Returns `None` for [`Course::Raw`](Course::Raw).
+            pub fn component(&self) -> Option<&str> {
+                match self {
+                    #(Course::#names9 => #components,)*
+                    Course::Raw(_) => None,
+                }
+            }
+
+            /// Title of the course, such as `Introduction to Computer Science I`.
+            ///
+            /// Returns `None` for [`Course::Raw`](Course::Raw) or if the title isn't in the
+            /// catalog.
+            pub fn title(&self) -> Option<&str> {
+                match self {
+                    #(Course::#names10 => #titles,)*
+                    Course::Raw(_) => None,
+                }
+            }
+
+            /// Credit hours of the course.
+            ///
+            /// Returns `None` for [`Course::Raw`](Course::Raw) or if the credit hours aren't in
+            /// the catalog.
+            pub fn credits(&self) -> Option<Credits> {
+                match self {
+                    #(Course::#names11 => #credits,)*
+                    Course::Raw(_) => None,
+                }
+            }
+
+            /// Pathways, or general education designations, fulfilled by the course.
+            ///
+            /// Returns an empty slice for [`Course::Raw`](Course::Raw) or if the course doesn't
+            /// fulfill any pathways.
+            pub fn pathways(&self) -> &[&str] {
+                match self {
+                    #(Course::#names12 => #pathways,)*
+                    Course::Raw(_) => &[],
+                }
+            }
+
+            /// Department offering the course, such as `Computer Science and Engineering`.
+            ///
+            /// Returns `None` for [`Course::Raw`](Course::Raw) or if the department isn't in the
+            /// catalog.
+            pub fn department(&self) -> Option<&str> {
+                match self {
+                    #(Course::#names13 => #departments,)*
+                    Course::Raw(_) => None,
+                }
+            }
         }
 
-        // TODO: rust-phf could be more optimal
-        // https://github.com/rust-phf/rust-phf
+        /// Courses by their normalized name, including names without a component.
+        static COURSES_BY_NAME: phf::Map<&'static str, Course> = #names_map;
+
+        /// Courses by their internal id.
+        static COURSES_BY_ID: phf::Map<&'static str, Course> = #ids_map;
+
         impl FromStr for Course {
             type Err = ParseIdError;
 
+            /// Parse a course in the form of its subject, catalog number, and optionally its
+            /// component, such as `CSE 115`, `cse-115`, or `CSE115 LLR`.
+            ///
+            /// If the component isn't specified, the lecture is chosen, or otherwise the first
+            /// component alphabetically.
             fn from_str(s: &str) -> Result<Self, Self::Err> {
-                match &*normalize(s) {
-                    #(#names5 => Ok(Course::#names6),)*
-                    _ => Err(ParseIdError::InvalidId {
-                        id: "Course".to_owned(),
+                match COURSES_BY_NAME.get(&*infer::normalize_course_name(s)) {
+                    Some(course) => Ok(course.clone()),
+                    None => Err(ParseIdError::InvalidCourse {
                         given: s.to_owned(),
                     }),
                 }
             }
         }
 
-        /// An enum of available semesters in the catalog.
-        ///
-        /// If a semester is missing, manually specify its id with [`Semester::Raw`](Semester::Raw) and
-        /// consider sending a PR adding that mapping.
-        // TODO: auto-gen semesters?
-        #[derive(Debug, Clone)]
-        #[non_exhaustive]
-        pub enum Semester {
-            Spring2023,
-            Summer2023,
-            Fall2023,
-            Winter2024,
-            Spring2024,
-            Raw(String),
+        impl PartialEq for Course {
+            /// Courses are equal if their ids are equal, including [`Course::Raw`](Course::Raw).
+            fn eq(&self, other: &Self) -> bool {
+                self.id() == other.id()
+            }
         }
 
-        impl Semester {
-            /// Internal id of the semester.
-            pub fn id(&self) -> &str {
+        impl Eq for Course {}
+
+        impl Hash for Course {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                self.id().hash(state);
+            }
+        }
+
+        #[cfg(feature = "serde_support")]
+        impl serde::Serialize for Course {
+            /// Serialize the course as its normalized name, such as `CSE115LLR`, or
+            /// [`Course::Raw`](Course::Raw) as its id.
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
                 match self {
-                    Semester::Spring2023 => "2231",
-                    Semester::Summer2023 => "2236",
-                    Semester::Fall2023 => "2239",
-                    Semester::Winter2024 => "2240",
-                    Semester::Spring2024 => "2241",
-                    Semester::Raw(id) => id,
+                    Course::Raw(id) => serializer.serialize_str(id),
+                    course => {
+                        serializer.serialize_str(&infer::normalize_course_name(&course.to_string()))
+                    }
                 }
             }
         }
 
-        impl FromStr for Semester {
-            type Err = ParseIdError;
-
-            fn from_str(s: &str) -> Result<Self, Self::Err> {
-                match &*normalize(s) {
-                    "SPRING2023" => Ok(Semester::Spring2023),
-                    "SUMMER2023" => Ok(Semester::Summer2023),
-                    "FALL2023" => Ok(Semester::Fall2023),
-                    "WINTER2024" => Ok(Semester::Winter2024),
-                    "SPRING2024" => Ok(Semester::Spring2024),
-                    _ => Err(ParseIdError::InvalidId {
-                        id: "Semester".to_owned(),
-                        given: s.to_owned(),
-                    }),
+        #[cfg(feature = "serde_support")]
+        impl<'de> serde::Deserialize<'de> for Course {
+            /// Deserialize the course from its name via [`FromStr`](FromStr), or from its id via
+            /// [`Course::from_id`](Course::from_id).
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                let s = String::deserialize(deserializer)?;
+                if !s.is_empty() && s.bytes().all(|byte| byte.is_ascii_digit()) {
+                    return Ok(Course::from_id(&s));
                 }
+
+                s.parse().map_err(serde::de::Error::custom)
             }
         }
 
@@ -168,6 +351,20 @@ fn generate_tokens(courses: HashMap<String, Course>) -> io::Result<TokenStream>
         }
 
         impl Career {
+            /// Construct a [`Career`](Career) from its internal id, or
+            /// [`Career::Raw`](Career::Raw) if it is unknown.
+            pub fn from_id(id: &str) -> Career {
+                match id {
+                    "UGRD" => Career::Undergraduate,
+                    "GRAD" => Career::Graduate,
+                    "LAW" => Career::Law,
+                    "SDM" => Career::DentalMedicine,
+                    "MED" => Career::Medicine,
+                    "PHRM" => Career::Pharmacy,
+                    _ => Career::Raw(id.to_owned()),
+                }
+            }
+
             /// Internal id of the career.
             pub fn id(&self) -> &str {
                 match self {
@@ -201,6 +398,65 @@ fn generate_tokens(courses: HashMap<String, Course>) -> io::Result<TokenStream>
             }
         }
 
+        impl fmt::Display for Career {
+            /// Format the career as its name, such as `Dental Medicine`, or
+            /// [`Career::Raw`](Career::Raw) as its id.
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self {
+                    Career::Undergraduate => write!(f, "Undergraduate"),
+                    Career::Graduate => write!(f, "Graduate"),
+                    Career::Law => write!(f, "Law"),
+                    Career::DentalMedicine => write!(f, "Dental Medicine"),
+                    Career::Medicine => write!(f, "Medicine"),
+                    Career::Pharmacy => write!(f, "Pharmacy"),
+                    Career::Raw(id) => write!(f, "{id}"),
+                }
+            }
+        }
+
+        impl PartialEq for Career {
+            /// Careers are equal if their ids are equal, including [`Career::Raw`](Career::Raw).
+            fn eq(&self, other: &Self) -> bool {
+                self.id() == other.id()
+            }
+        }
+
+        impl Eq for Career {}
+
+        impl Hash for Career {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                self.id().hash(state);
+            }
+        }
+
+        #[cfg(feature = "serde_support")]
+        impl serde::Serialize for Career {
+            /// Serialize the career as its name, such as `DentalMedicine`, or
+            /// [`Career::Raw`](Career::Raw) as its id.
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                match self {
+                    Career::Raw(id) => serializer.serialize_str(id),
+                    career => serializer.serialize_str(&career.to_string().replace(' ', "")),
+                }
+            }
+        }
+
+        #[cfg(feature = "serde_support")]
+        impl<'de> serde::Deserialize<'de> for Career {
+            /// Deserialize the career from its name via [`FromStr`](FromStr), or otherwise from
+            /// its id via [`Career::from_id`](Career::from_id).
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                let s = String::deserialize(deserializer)?;
+                Ok(s.parse().unwrap_or_else(|_| Career::from_id(&s)))
+            }
+        }
+
         /// Normalize the input string for use in [`FromStr`](std::str:FromStr) implementations.
         fn normalize(s: &str) -> String {
             s.chars()
@@ -217,24 +473,63 @@ fn generate_tokens(courses: HashMap<String, Course>) -> io::Result<TokenStream>
             /// Considering using the `Raw` variant for specifying raw ids.
             #[error("`{given}` is an invalid `{id}``")]
             InvalidId { id: String, given: String },
+            /// Specified course isn't in the catalog.
+            ///
+            /// Courses with similar names can be found via
+            /// [`ParseIdError::suggestions`](ParseIdError::suggestions).
+            #[error("`{given}` is an invalid `Course`")]
+            InvalidCourse { given: String },
         }
     ))
 }
 
 fn main() {
     println!("cargo:rerun-if-changed={COURSES_PATH}");
+    println!("cargo:rerun-if-changed=src/infer.rs");
 
     let mut courses = HashMap::new();
 
-    let mut reader = Reader::from_path(COURSES_PATH).unwrap();
+    let mut reader = ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_path(COURSES_PATH)
+        .unwrap();
     for result in reader.records() {
         let record = result.unwrap();
+        if !infer::is_valid_course_id(&record[0]) {
+            panic!("invalid course id `{}`", &record[0]);
+        }
+        let name = record[2].to_owned();
+        // If the career isn't specified, infer it from the catalog number
+        let career = match &record[1] {
+            "" => {
+                let (subject, catalog_number, _) = infer::split_course_name(&name)
+                    .unwrap_or_else(|| panic!("invalid course name `{name}`"));
+                infer::infer_career_id(subject, catalog_number.parse().unwrap()).to_owned()
+            }
+            career => career.to_owned(),
+        };
+
+        let field = |index| match record.get(index).map(str::trim) {
+            Some("") | None => None,
+            Some(field) => Some(field),
+        };
+        let credits = field(4).map(|credits| {
+            infer::parse_credits(credits)
+                .unwrap_or_else(|| panic!("invalid credits `{credits}` for course `{name}`"))
+        });
+        let pathways = field(5).map(infer::split_pathways).unwrap_or_default();
+
         courses.insert(
             record[0].to_owned(),
             Course {
                 // id: record[0].to_owned(),
-                career: record[1].to_owned(),
-                name: record[2].to_owned(),
+                career,
+                title: field(3).map(str::to_owned),
+                credits,
+                pathways,
+                department: field(6).map(str::to_owned),
+                name,
             },
         );
     }
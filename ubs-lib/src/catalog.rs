@@ -0,0 +1,285 @@
+//! Catalog of courses loaded at runtime.
+//!
+//! The [`Course`](crate::Course) enum is generated from `data/courses.csv` at compile time.
+//! A [`Catalog`](Catalog) holds the same information, though it can be loaded at runtime,
+//! allowing courses to be added without rebuilding.
+
+use std::{
+    collections::HashMap,
+    io::{Read, Write},
+};
+
+#[cfg(feature = "serde_support")]
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{fuzzy, infer, Career, Course, Credits};
+
+/// A course in a [`Catalog`](Catalog).
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub struct CatalogEntry {
+    /// Internal id of the course, such as `004229`.
+    pub id: String,
+    /// Internal id of the career offering the course, such as `UGRD`, or empty to infer it.
+    #[cfg_attr(feature = "serde_support", serde(default))]
+    pub career: String,
+    /// Name of the course, such as `CSE115LLR`.
+    pub name: String,
+    /// Title of the course, such as `Introduction to Computer Science I`.
+    #[cfg_attr(feature = "serde_support", serde(default))]
+    pub title: Option<String>,
+    /// Credit hours of the course.
+    #[cfg_attr(feature = "serde_support", serde(default))]
+    pub credits: Option<Credits>,
+    /// Pathways, or general education designations, fulfilled by the course.
+    #[cfg_attr(feature = "serde_support", serde(default))]
+    pub pathways: Vec<String>,
+    /// Department offering the course, such as `Computer Science and Engineering`.
+    #[cfg_attr(feature = "serde_support", serde(default))]
+    pub department: Option<String>,
+}
+
+/// Catalog of courses, mapping their names and ids to careers.
+///
+/// Lookups that miss the catalog fall back to the compiled [`Course`](Course) enum, thus an
+/// empty catalog, as constructed by [`Catalog::default`](Catalog::default), behaves exactly
+/// like the enum.
+#[derive(Debug, Clone, Default)]
+pub struct Catalog {
+    entries: Vec<CatalogEntry>,
+    // id -> index of entry
+    ids: HashMap<String, usize>,
+    // normalized name -> index of entry
+    names: HashMap<String, usize>,
+}
+
+impl Catalog {
+    /// Construct a new [`Catalog`](Catalog) from the specified entries.
+    ///
+    /// Entries without a career have it inferred from their name via
+    /// [`Career::infer`](Career::infer). If multiple entries share an id or name, the last one
+    /// takes precedence.
+    pub fn new<I>(entries: I) -> Result<Self, CatalogError>
+    where
+        I: IntoIterator<Item = CatalogEntry>,
+    {
+        let mut catalog = Catalog::default();
+        // (subject + catalog number) -> (component, index of entry)
+        let mut defaults: HashMap<String, (String, usize)> = HashMap::new();
+        for mut entry in entries {
+            if !infer::is_valid_course_id(&entry.id) {
+                return Err(CatalogError::InvalidId(entry.id));
+            }
+
+            let name = infer::normalize_course_name(&entry.name);
+            let (subject, catalog_number, component) = infer::split_course_name(&name)
+                .ok_or_else(|| CatalogError::InvalidName(entry.name.clone()))?;
+            if entry.career.is_empty() {
+                let catalog_number = catalog_number
+                    .parse()
+                    .map_err(|_| CatalogError::InvalidName(entry.name.clone()))?;
+                entry.career = infer::infer_career_id(subject, catalog_number).to_owned();
+            }
+
+            let index = catalog.entries.len();
+            if !component.is_empty() {
+                // Same as the `Course` enum, default to the preferred component
+                let key = format!("{subject}{catalog_number}");
+                match defaults.get(&key) {
+                    Some((default, _)) if !infer::is_preferred_component(component, default) => {}
+                    _ => {
+                        defaults.insert(key, (component.to_owned(), index));
+                    }
+                }
+            }
+
+            catalog.ids.insert(entry.id.clone(), index);
+            catalog.names.insert(name, index);
+            catalog.entries.push(entry);
+        }
+
+        for (key, (_, index)) in defaults {
+            catalog.names.entry(key).or_insert(index);
+        }
+
+        Ok(catalog)
+    }
+
+    /// Load a catalog from CSV in the same format as `data/courses.csv`.
+    ///
+    /// Each row consists of the course id, the career id (inferred if empty), the name, the
+    /// title, the credit hours (e.g. `4` or `1-3`), the pathways separated by semicolons, and
+    /// the department, without a header row. Every column after the name is optional.
+    pub fn from_csv<R>(reader: R) -> Result<Self, CatalogError>
+    where
+        R: Read,
+    {
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(reader);
+
+        let mut entries = Vec::new();
+        for record in reader.records() {
+            let record = record?;
+            let field = |index: usize| record.get(index).unwrap_or_default().trim();
+            let optional = |index: usize| Some(field(index)).filter(|field| !field.is_empty());
+
+            let credits = optional(4)
+                .map(|credits| {
+                    credits
+                        .parse()
+                        .map_err(|_| CatalogError::InvalidCredits(credits.to_owned()))
+                })
+                .transpose()?;
+            entries.push(CatalogEntry {
+                id: field(0).to_owned(),
+                career: field(1).to_owned(),
+                name: field(2).to_owned(),
+                title: optional(3).map(str::to_owned),
+                credits,
+                pathways: infer::split_pathways(field(5)),
+                department: optional(6).map(str::to_owned),
+            });
+        }
+
+        Self::new(entries)
+    }
+
+    /// Load a catalog from a JSON array of [`CatalogEntry`](CatalogEntry) objects.
+    ///
+    /// For instance, `[{"id": "004544", "career": "UGRD", "name": "CSE115LLR"}]`, where the
+    /// career may be omitted to infer it, and every field after the name may be omitted.
+    #[cfg(feature = "serde_support")]
+    pub fn from_json<R>(reader: R) -> Result<Self, CatalogError>
+    where
+        R: Read,
+    {
+        let entries: Vec<CatalogEntry> = serde_json::from_reader(reader)?;
+        Self::new(entries)
+    }
+
+    /// Write the catalog as CSV in the same format as `data/courses.csv`.
+    ///
+    /// Entries are written in the order they were loaded, omitting trailing columns that are
+    /// empty.
+    pub fn write_csv<W>(&self, writer: W) -> Result<(), CatalogError>
+    where
+        W: Write,
+    {
+        let mut writer = csv::WriterBuilder::new()
+            .flexible(true)
+            .terminator(csv::Terminator::CRLF)
+            .from_writer(writer);
+
+        for entry in &self.entries {
+            let mut record = vec![
+                entry.id.clone(),
+                entry.career.clone(),
+                entry.name.clone(),
+                entry.title.clone().unwrap_or_default(),
+                entry
+                    .credits
+                    .map(|credits| credits.to_string())
+                    .unwrap_or_default(),
+                entry.pathways.join("; "),
+                entry.department.clone().unwrap_or_default(),
+            ];
+            while record.last().is_some_and(String::is_empty) {
+                record.pop();
+            }
+
+            writer.write_record(&record)?;
+        }
+
+        writer.flush().map_err(csv::Error::from)?;
+        Ok(())
+    }
+
+    /// Iterator over every entry in the catalog, in the order they were loaded.
+    pub fn entries(&self) -> impl Iterator<Item = &CatalogEntry> + '_ {
+        self.entries.iter()
+    }
+
+    /// Get the entry with the specified id or name.
+    ///
+    /// Names are matched the same as when parsing a [`Course`](Course), for instance, `CSE 115`
+    /// or `cse-115-llr`.
+    pub fn get(&self, id_or_name: &str) -> Option<&CatalogEntry> {
+        let index = match self.ids.get(id_or_name.trim()) {
+            Some(index) => index,
+            None => self.names.get(&infer::normalize_course_name(id_or_name))?,
+        };
+
+        self.entries.get(*index)
+    }
+
+    /// Get the course with the specified id or name.
+    ///
+    /// Courses in the catalog that are also in the [`Course`](Course) enum are returned as
+    /// such, otherwise they are returned as [`Course::Raw`](Course::Raw). If the course isn't
+    /// in the catalog, it is parsed via the enum.
+    pub fn course(&self, id_or_name: &str) -> Option<Course> {
+        match self.get(id_or_name) {
+            Some(entry) => Some(Course::from_id(&entry.id)),
+            None => id_or_name.parse().ok(),
+        }
+    }
+
+    /// Get the courses with names most similar to the specified name, including those in the
+    /// [`Course`](Course) enum.
+    ///
+    /// See [`Course::suggestions`](Course::suggestions) for details.
+    pub fn suggestions(&self, name: &str) -> Vec<Course> {
+        let entries = self.entries.iter().filter_map(|entry| {
+            Some((
+                infer::normalize_course_name(&entry.name),
+                self.course(&entry.id)?,
+            ))
+        });
+        let courses = Course::ALL
+            .iter()
+            .filter(|course| !self.ids.contains_key(course.id()))
+            .map(|course| {
+                (
+                    infer::normalize_course_name(&course.to_string()),
+                    course.clone(),
+                )
+            });
+
+        fuzzy::closest(&infer::normalize_course_name(name), entries.chain(courses))
+    }
+
+    /// Get the career offering the specified course.
+    ///
+    /// If the course isn't in the catalog, the career is inferred via
+    /// [`Course::career`](Course::career).
+    pub fn career(&self, course: &Course) -> Option<Career> {
+        match self.ids.get(course.id()) {
+            Some(index) => Some(Career::from_id(&self.entries[*index].career)),
+            None => course.career(),
+        }
+    }
+}
+
+/// Error when loading a catalog.
+#[derive(Debug, Error)]
+pub enum CatalogError {
+    /// Failed to read or parse CSV.
+    #[error(transparent)]
+    InvalidCsv(#[from] csv::Error),
+    /// Failed to read or parse JSON.
+    #[cfg(feature = "serde_support")]
+    #[error(transparent)]
+    InvalidJson(#[from] serde_json::Error),
+    /// Course id isn't six digits.
+    #[error("`{0}` is an invalid course id")]
+    InvalidId(String),
+    /// Course name isn't in the form of a subject, catalog number, and component.
+    #[error("`{0}` is an invalid course name")]
+    InvalidName(String),
+    /// Credit hours aren't a number or a range of numbers.
+    #[error("`{0}` are invalid credit hours")]
+    InvalidCredits(String),
+}
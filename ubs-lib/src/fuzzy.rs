@@ -0,0 +1,83 @@
+//! Fuzzy matching of course names for suggestions.
+
+/// Maximum amount of suggestions returned by [`closest`](closest).
+const MAX_SUGGESTIONS: usize = 3;
+/// Maximum length of a name matched by [`closest`](closest).
+///
+/// Course names are at most about a dozen characters, thus a name this long is further than a
+/// third of its length from every candidate, and comparing it would only be costly.
+const MAX_NAME_LEN: usize = 32;
+
+/// Get the candidates closest to the normalized name, ordered from closest to furthest.
+///
+/// Each candidate is a normalized name and its value. Candidates are matched both with and without
+/// their component, and only those within an edit distance of a third of the length of the name
+/// are returned. Ties are broken in favor of candidates with a similar subject and catalog number,
+/// as the component is the most commonly mistyped.
+pub(crate) fn closest<T, I>(name: &str, candidates: I) -> Vec<T>
+where
+    I: IntoIterator<Item = (String, T)>,
+{
+    if name.len() > MAX_NAME_LEN {
+        return Vec::new();
+    }
+
+    let max_distance = (name.len() / 3).max(1);
+    let (subject, base) = split(name);
+
+    let mut matches: Vec<_> = candidates
+        .into_iter()
+        .filter_map(|(candidate, value)| {
+            let (candidate_subject, candidate_base) = split(&candidate);
+            let distance = edit_distance(name, &candidate).min(edit_distance(name, candidate_base));
+            let rank = (
+                distance,
+                edit_distance(base, candidate_base),
+                edit_distance(subject, candidate_subject),
+            );
+
+            (distance <= max_distance).then_some((rank, candidate, value))
+        })
+        .collect();
+    matches.sort_by(|(a_rank, a_name, _), (b_rank, b_name, _)| {
+        a_rank.cmp(b_rank).then_with(|| a_name.cmp(b_name))
+    });
+
+    matches
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, _, value)| value)
+        .collect()
+}
+
+/// Split the name into its subject and its subject followed by its catalog number, such as
+/// `("CSE", "CSE115")` for `CSE115LLR`.
+fn split(name: &str) -> (&str, &str) {
+    let number_start = name
+        .find(|c: char| c.is_ascii_digit())
+        .unwrap_or(name.len());
+    let number_end = name[number_start..]
+        .find(|c: char| !c.is_ascii_digit())
+        .map_or(name.len(), |end| number_start + end);
+
+    (&name[..number_start], &name[..number_end])
+}
+
+/// Levenshtein distance between two strings, counting insertions, deletions, and substitutions.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    // Distances from the current prefix of `a` to every prefix of `b`
+    let mut distances: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut diagonal = distances[0];
+        distances[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(a_char != *b_char);
+            diagonal = distances[j + 1];
+            distances[j + 1] = substitution.min(distances[j] + 1).min(distances[j + 1] + 1);
+        }
+    }
+
+    distances[b.len()]
+}
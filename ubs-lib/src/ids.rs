@@ -1 +1,369 @@
 include!(concat!(env!("OUT_DIR"), "/ids.rs"));
+
+use std::{fmt, ops::RangeInclusive};
+
+use chrono::{Datelike, Local, NaiveDate};
+
+use crate::{fuzzy, infer};
+
+// Years representable by semester ids.
+const YEARS: RangeInclusive<u16> = 1900..=2799;
+
+impl Course {
+    /// Get the courses with names most similar to the specified name, such as `CSE115LEC` for
+    /// `CSE 115 LLC`, ordered from most to least similar.
+    ///
+    /// At most 3 courses are returned, and if no courses are similar, none are returned.
+    pub fn suggestions(name: &str) -> Vec<Course> {
+        fuzzy::closest(
+            &infer::normalize_course_name(name),
+            Course::ALL.iter().map(|course| {
+                (
+                    infer::normalize_course_name(&course.to_string()),
+                    course.clone(),
+                )
+            }),
+        )
+    }
+}
+
+impl fmt::Display for Course {
+    /// Format the course in its canonical catalog form, such as `CSE 115 LLR`.
+    ///
+    /// [`Course::Raw`](Course::Raw) is formatted as its id.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.subject(), self.catalog_number(), self.component()) {
+            (Some(subject), Some(catalog_number), Some(component)) => {
+                write!(f, "{subject} {catalog_number} {component}")
+            }
+            (Some(subject), Some(catalog_number), None) => write!(f, "{subject} {catalog_number}"),
+            _ => write!(f, "{}", self.id()),
+        }
+    }
+}
+
+impl ParseIdError {
+    /// Get the courses with names most similar to the course that failed to parse, as per
+    /// [`Course::suggestions`](Course::suggestions).
+    ///
+    /// Suggestions are computed on each call rather than when parsing, so that rejecting an
+    /// unknown course stays cheap. Errors other than
+    /// [`ParseIdError::InvalidCourse`](ParseIdError::InvalidCourse) have no suggestions.
+    pub fn suggestions(&self) -> Vec<Course> {
+        match self {
+            ParseIdError::InvalidCourse { given } => Course::suggestions(given),
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Credit hours of a course.
+///
+/// Most courses are worth a fixed amount of credit hours, in which case `min` and `max` are
+/// equal, while others, such as independent studies, are worth a variable amount.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+    feature = "serde_support",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct Credits {
+    pub min: f32,
+    pub max: f32,
+}
+
+impl Credits {
+    /// Whether the course is worth a variable amount of credit hours.
+    pub fn is_variable(&self) -> bool {
+        self.min != self.max
+    }
+}
+
+impl fmt::Display for Credits {
+    /// Format the credit hours as a number or a range, such as `4` or `1-3`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.is_variable() {
+            true => write!(f, "{}-{}", self.min, self.max),
+            false => write!(f, "{}", self.min),
+        }
+    }
+}
+
+impl FromStr for Credits {
+    type Err = ParseIdError;
+
+    /// Parse credit hours in the form of a number or a range, such as `4` or `1-3`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (min, max) = infer::parse_credits(s).ok_or_else(|| ParseIdError::InvalidId {
+            id: "Credits".to_owned(),
+            given: s.to_owned(),
+        })?;
+        Ok(Self { min, max })
+    }
+}
+
+/// Season of a semester, in chronological order within a year.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Season {
+    Winter,
+    Spring,
+    Summer,
+    Fall,
+}
+
+impl Season {
+    /// Last digit of the ids of semesters in this season.
+    fn digit(&self) -> u32 {
+        match self {
+            Season::Winter => 0,
+            Season::Spring => 1,
+            Season::Summer => 6,
+            Season::Fall => 9,
+        }
+    }
+
+    fn from_digit(digit: u32) -> Option<Self> {
+        match digit {
+            0 => Some(Season::Winter),
+            1 => Some(Season::Spring),
+            6 => Some(Season::Summer),
+            9 => Some(Season::Fall),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Season {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Season::Winter => write!(f, "Winter"),
+            Season::Spring => write!(f, "Spring"),
+            Season::Summer => write!(f, "Summer"),
+            Season::Fall => write!(f, "Fall"),
+        }
+    }
+}
+
+impl FromStr for Season {
+    type Err = ParseIdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match &*normalize(s) {
+            "WINTER" => Ok(Season::Winter),
+            "SPRING" => Ok(Season::Spring),
+            "SUMMER" => Ok(Season::Summer),
+            "FALL" => Ok(Season::Fall),
+            _ => Err(ParseIdError::InvalidId {
+                id: "Season".to_owned(),
+                given: s.to_owned(),
+            }),
+        }
+    }
+}
+
+/// A semester, identified by its season and year.
+///
+/// Semesters are ordered chronologically, where the winter session of a year precedes the spring
+/// semester of that same year.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Semester {
+    // Order of fields determines the derived ordering
+    year: u16,
+    season: Season,
+}
+
+impl Semester {
+    /// Construct a new [`Semester`](Semester) from its season and year.
+    ///
+    /// Note that ids are only defined for years from 1900 to 2799.
+    pub const fn new(season: Season, year: u16) -> Self {
+        Self { year, season }
+    }
+
+    /// Construct a [`Semester`](Semester) from its internal id.
+    ///
+    /// For instance, `2241` is Spring 2024.
+    pub fn from_id(id: &str) -> Result<Self, ParseIdError> {
+        let invalid = || ParseIdError::InvalidId {
+            id: "Semester".to_owned(),
+            given: id.to_owned(),
+        };
+
+        let id = id.trim();
+        if id.len() != 4 || !id.bytes().all(|byte| byte.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        // Unwrap because every byte is a digit
+        let id: u32 = id.parse().unwrap();
+
+        let century = id / 1000;
+        if century == 0 {
+            return Err(invalid());
+        }
+        let year = (century + 18) * 100 + (id / 10) % 100;
+        let season = Season::from_digit(id % 10).ok_or_else(invalid)?;
+        Ok(Self::new(season, year as u16))
+    }
+
+    /// Get the semester in session, or the last semester to end, on the specified date.
+    ///
+    /// Note that the boundaries between semesters are approximate, the winter session ends by
+    /// January 20th, the spring semester by May 20th, and the summer session by August 20th.
+    pub fn from_date(date: NaiveDate) -> Self {
+        let season = match (date.month(), date.day()) {
+            (1, ..=20) => Season::Winter,
+            (1..=4, _) | (5, ..=20) => Season::Spring,
+            (5..=7, _) | (8, ..=20) => Season::Summer,
+            _ => Season::Fall,
+        };
+        Self::new(season, date.year() as u16)
+    }
+
+    /// Get the semester in session today.
+    ///
+    /// See [`Semester::from_date`](Semester::from_date) for details.
+    pub fn current() -> Self {
+        Self::from_date(Local::now().date_naive())
+    }
+
+    /// Get the semester following this one.
+    pub fn next(&self) -> Self {
+        match self.season {
+            Season::Winter => Self::new(Season::Spring, self.year),
+            Season::Spring => Self::new(Season::Summer, self.year),
+            Season::Summer => Self::new(Season::Fall, self.year),
+            Season::Fall => Self::new(Season::Winter, self.year + 1),
+        }
+    }
+
+    /// Season of the semester.
+    pub fn season(&self) -> Season {
+        self.season
+    }
+
+    /// Year of the semester.
+    pub fn year(&self) -> u16 {
+        self.year
+    }
+
+    /// Internal id of the semester.
+    ///
+    /// The id is composed of the century (counting from 1800), the last two digits of the year,
+    /// and a digit for the season. For instance, `2231` is Spring 2023, `2236` is Summer 2023,
+    /// `2239` is Fall 2023, and `2240` is Winter 2024.
+    pub fn id(&self) -> String {
+        let year = u32::from(self.year);
+        format!(
+            "{}{:02}{}",
+            (year / 100).saturating_sub(18),
+            year % 100,
+            self.season.digit()
+        )
+    }
+}
+
+impl fmt::Display for Semester {
+    /// Format the semester as its season followed by its year, such as `Spring 2024`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.season, self.year)
+    }
+}
+
+impl FromStr for Semester {
+    type Err = ParseIdError;
+
+    /// Parse a semester in the form of its season followed by its year, such as `Fall2031` or
+    /// `Spring 2024`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseIdError::InvalidId {
+            id: "Semester".to_owned(),
+            given: s.to_owned(),
+        };
+
+        let normalized = normalize(s);
+        let split = normalized
+            .find(|c: char| c.is_ascii_digit())
+            .ok_or_else(invalid)?;
+        let (season, year) = normalized.split_at(split);
+        if year.len() != 4 {
+            return Err(invalid());
+        }
+
+        let year = year.parse().map_err(|_| invalid())?;
+        if !YEARS.contains(&year) {
+            return Err(invalid());
+        }
+
+        Ok(Self::new(season.parse().map_err(|_| invalid())?, year))
+    }
+}
+
+impl Career {
+    /// Infer the career offering a course from its subject and catalog number.
+    ///
+    /// Courses of the dental and medical schools, such as `ORB` and `MED` courses, are
+    /// [`Career::DentalMedicine`](Career::DentalMedicine) and [`Career::Medicine`](Career::Medicine)
+    /// respectively. Otherwise, courses numbered 500 and above are
+    /// [`Career::Graduate`](Career::Graduate), except for those of the law and pharmacy schools,
+    /// such as `LAW` courses being [`Career::Law`](Career::Law) and `PHM` courses being
+    /// [`Career::Pharmacy`](Career::Pharmacy). Every other course is
+    /// [`Career::Undergraduate`](Career::Undergraduate).
+    pub fn infer(subject: &str, catalog_number: u32) -> Career {
+        Career::from_id(infer::infer_career_id(subject, catalog_number))
+    }
+
+    /// Infer the career offering a course from its name, such as `CSE 574` or `LAW505SEM`.
+    ///
+    /// See [`Career::infer`](Career::infer) for details.
+    pub fn infer_from_name(name: &str) -> Option<Career> {
+        let name = normalize(name);
+        let (subject, catalog_number, _) = infer::split_course_name(&name)?;
+        Some(Career::infer(subject, catalog_number.parse().ok()?))
+    }
+}
+
+#[cfg(feature = "serde_support")]
+impl serde::Serialize for Season {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde_support")]
+impl<'de> serde::Deserialize<'de> for Season {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(feature = "serde_support")]
+impl serde::Serialize for Semester {
+    /// Serialize the semester as its season followed by its year, such as `Spring2024`.
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(&format_args!("{}{}", self.season, self.year))
+    }
+}
+
+#[cfg(feature = "serde_support")]
+impl<'de> serde::Deserialize<'de> for Semester {
+    /// Deserialize the semester from its season and year via [`FromStr`](FromStr), or from its
+    /// id via [`Semester::from_id`](Semester::from_id).
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse()
+            .or_else(|_| Semester::from_id(&s))
+            .map_err(serde::de::Error::custom)
+    }
+}
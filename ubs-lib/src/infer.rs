@@ -0,0 +1,103 @@
+//! Rules for inferring information about courses, shared with the build script.
+
+/// Normalize a course name for lookups, such as `cse 115-llr` to `CSE115LLR`.
+pub(crate) fn normalize_course_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| !c.is_whitespace() && *c != '-' && *c != '_')
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+/// Whether the id is a valid course id, consisting of six digits such as `004544`.
+pub(crate) fn is_valid_course_id(id: &str) -> bool {
+    id.len() == 6 && id.bytes().all(|byte| byte.is_ascii_digit())
+}
+
+/// Split a course name into its subject, catalog number, and component.
+///
+/// For instance, `CSE115LLR` is split into `("CSE", "115", "LLR")`, while `CSE115` is split into
+/// `("CSE", "115", "")`.
+pub(crate) fn split_course_name(name: &str) -> Option<(&str, &str, &str)> {
+    let number_start = name.find(|c: char| c.is_ascii_digit())?;
+    let number_end = name[number_start..]
+        .find(|c: char| !c.is_ascii_digit())
+        .map_or(name.len(), |end| number_start + end);
+
+    let subject = &name[..number_start];
+    if subject.is_empty() || !subject.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+
+    let component = &name[number_end..];
+    if !component.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+
+    Some((subject, &name[number_start..number_end], component))
+}
+
+/// Whether the component should replace the current default component of a course, used when
+/// the course is specified without one.
+///
+/// The lecture is preferred, otherwise the first component alphabetically.
+pub(crate) fn is_preferred_component(component: &str, default: &str) -> bool {
+    (component != "LEC", component) < (default != "LEC", default)
+}
+
+/// Infer the id of the career offering a course from its subject and catalog number.
+///
+/// Courses of the dental and medical schools are offered by their own careers regardless of their
+/// catalog number, as those subjects aren't offered to other students. Otherwise, courses numbered
+/// 500 and above are graduate level, except for those offered by the law and pharmacy schools,
+/// which have their own careers.
+pub(crate) fn infer_career_id(subject: &str, catalog_number: u32) -> &'static str {
+    let subject = subject.to_ascii_uppercase();
+    if DENTAL_SUBJECTS.contains(&subject.as_str()) {
+        return "SDM";
+    }
+    if MEDICAL_SUBJECTS.contains(&subject.as_str()) {
+        return "MED";
+    }
+
+    if catalog_number < 500 {
+        return "UGRD";
+    }
+
+    match subject.as_str() {
+        "LAW" => "LAW",
+        "PHM" => "PHRM",
+        _ => "GRAD",
+    }
+}
+
+// Neither list is verified against the class search, as `courses.csv` has no dental or medical
+// courses yet. They're abbreviations of the departments listed by each school, thus once a
+// course is recorded with an explicit career, its subject should be checked against them.
+
+/// Subjects offered only by the School of Dental Medicine, abbreviating its departments of Oral
+/// Biology, Oral Diagnostic Sciences, Orthodontics, Periodontics and Endodontics, and
+/// Restorative Dentistry.
+const DENTAL_SUBJECTS: &[&str] = &["ORB", "ODS", "ORT", "PER", "RSD"];
+
+/// Subjects offered only by the MD program of the Jacobs School of Medicine.
+const MEDICAL_SUBJECTS: &[&str] = &["MED"];
+
+/// Parse credit hours in the form of a number or a range, such as `4` or `1-3`, into
+/// `(min, max)`.
+pub(crate) fn parse_credits(credits: &str) -> Option<(f32, f32)> {
+    let (min, max) = credits.split_once('-').unwrap_or((credits, credits));
+    let min: f32 = min.trim().parse().ok()?;
+    let max: f32 = max.trim().parse().ok()?;
+
+    (min.is_finite() && max.is_finite() && 0.0 <= min && min <= max).then_some((min, max))
+}
+
+/// Split pathways separated by semicolons, such as `Global Pathway; Thematic Pathway`.
+pub(crate) fn split_pathways(pathways: &str) -> Vec<String> {
+    pathways
+        .split(';')
+        .map(str::trim)
+        .filter(|pathway| !pathway.is_empty())
+        .map(str::to_owned)
+        .collect()
+}
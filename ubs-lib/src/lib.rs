@@ -3,12 +3,12 @@
 //! ## Usage
 //! ```rust
 //! # use futures::stream::TryStreamExt;
-//! use ubs_lib::{Course, Semester};
+//! use ubs_lib::{Course, Season, Semester};
 //!
 //! # async fn run() -> Result<(), ubs_lib::ScheduleError> {
 //! let mut schedule_iter = ubs_lib::schedule_iter(
 //!     Course::Cse115Llr,
-//!     Semester::Spring2024,
+//!     Semester::new(Season::Spring, 2024),
 //! ).await?;
 //!
 //! while let Some(schedule) = schedule_iter.try_next().await? {
@@ -22,17 +22,41 @@
 //! # }
 //!```
 
+pub mod catalog;
+mod fuzzy;
 mod ids;
+mod infer;
 pub mod model;
 pub mod parser;
 pub mod session;
+pub mod transport;
 
-pub use ids::{Career, Course, ParseIdError, Semester};
+pub use catalog::Catalog;
+pub use ids::{Career, Course, Credits, ParseIdError, Season, Semester};
+use model::ClassScheduleModel;
+#[cfg(feature = "rustls")]
+use model::SearchResultModel;
 use parser::{ClassSchedule, ParseError};
-use session::{Query, Session, SessionError, Token};
+#[cfg(feature = "rustls")]
+use parser::{SearchResults, SemesterList};
+#[cfg(feature = "rustls")]
+use session::Token;
+use session::{Query, Session, SessionError};
+use transport::Transport;
+
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+
+#[cfg(feature = "rustls")]
+use futures::TryStream;
+use futures::{stream, Stream, StreamExt, TryStreamExt};
+#[cfg(feature = "rustls")]
+use hyper::{client::HttpConnector, Client};
 
-use futures::{TryStream, TryStreamExt};
-use hyper::Client;
+/// Default maximum amount of queries fetched at once by [`fetch_many`](fetch_many).
+pub const DEFAULT_CONCURRENCY: usize = 4;
 
 /// Iterator over each page of the specified query.
 ///
@@ -48,8 +72,24 @@ pub async fn schedule_iter<'a>(
     impl TryStream<Ok = Result<ClassSchedule, ParseError>, Error = SessionError> + 'a,
     ScheduleError,
 > {
-    let career = course
-        .career()
+    schedule_iter_with_catalog(course, semester, &Catalog::default()).await
+}
+
+/// Iterator over each page of the specified query, inferring the career from the catalog.
+///
+/// Courses missing from the catalog have their career inferred the same as in
+/// [`schedule_iter`](schedule_iter).
+#[cfg(feature = "rustls")]
+pub async fn schedule_iter_with_catalog<'a>(
+    course: Course,
+    semester: Semester,
+    catalog: &Catalog,
+) -> Result<
+    impl TryStream<Ok = Result<ClassSchedule, ParseError>, Error = SessionError> + 'a,
+    ScheduleError,
+> {
+    let career = catalog
+        .career(&course)
         .ok_or_else(|| ScheduleError::FailedToInferCareer(course.clone()))?;
     schedule_iter_with_career(course, semester, career).await
 }
@@ -68,21 +108,226 @@ pub async fn schedule_iter_with_career<'a>(
     impl TryStream<Ok = Result<ClassSchedule, ParseError>, Error = SessionError> + 'a,
     ScheduleError,
 > {
-    let client = Client::builder().build(
+    let session = session(&semester).await?;
+
+    Ok(session
+        .schedule_iter(Query::new(course, semester, career))
+        .map_ok(ClassSchedule::from_page))
+}
+
+/// Fetch the schedules of many courses in the same semester.
+///
+/// Courses are fetched concurrently, at most [`DEFAULT_CONCURRENCY`](DEFAULT_CONCURRENCY) at a
+/// time, and each result is yielded as it completes, thus not necessarily in the same order as
+/// `courses`. Courses whose career can't be inferred yield
+/// [`ScheduleError::FailedToInferCareer`](ScheduleError::FailedToInferCareer).
+///
+/// To fetch courses with an explicit career or to use a custom [`Session`](Session), see
+/// [`fetch_queries`](fetch_queries).
+#[cfg(feature = "rustls")]
+pub async fn fetch_many<I>(
+    courses: I,
+    semester: Semester,
+) -> Result<impl Stream<Item = Result<(Course, ClassScheduleModel), ScheduleError>>, ScheduleError>
+where
+    I: IntoIterator<Item = Course>,
+{
+    fetch_many_with_catalog(courses, semester, &Catalog::default()).await
+}
+
+/// Fetch the schedules of many courses in the same semester, inferring careers from the catalog.
+///
+/// See [`fetch_many`](fetch_many) for details.
+#[cfg(feature = "rustls")]
+pub async fn fetch_many_with_catalog<I>(
+    courses: I,
+    semester: Semester,
+    catalog: &Catalog,
+) -> Result<impl Stream<Item = Result<(Course, ClassScheduleModel), ScheduleError>>, ScheduleError>
+where
+    I: IntoIterator<Item = Course>,
+{
+    let mut queries = Vec::new();
+    let mut errors = Vec::new();
+    for course in courses {
+        match catalog.career(&course) {
+            Some(career) => queries.push(Query::new(course, semester, career)),
+            None => errors.push(Err(ScheduleError::FailedToInferCareer(course))),
+        }
+    }
+
+    // Initialized by `fetch_queries` for the semester
+    let client = client();
+    let token = Token::new(&client).await?;
+    let session = Session::new(client, token);
+    Ok(stream::iter(errors).chain(fetch_queries(&session, queries, DEFAULT_CONCURRENCY)))
+}
+
+/// Get every semester currently offered by the host in the form `(semester, name)`.
+#[cfg(feature = "rustls")]
+pub async fn semesters() -> Result<Vec<(Semester, String)>, ScheduleError> {
+    let client = client();
+    let token = Token::new(&client).await?;
+    let session = Session::new(client, token);
+    let bytes = session.semesters().await?;
+
+    Ok(SemesterList::new(bytes.into())?.semesters()?)
+}
+
+/// Search for courses offered in the specified semester matching the text.
+///
+/// For instance, `machine learning` or `CSE 4`. Each result can be turned into a course for
+/// [`schedule_iter_with_career`](schedule_iter_with_career) via
+/// [`SearchResultModel::course`](SearchResultModel::course), including courses that aren't in
+/// the catalog.
+#[cfg(feature = "rustls")]
+pub async fn search(
+    text: &str,
+    semester: Semester,
+) -> Result<Vec<SearchResultModel>, ScheduleError> {
+    let session = session(&semester).await?;
+    let bytes = session.search(text, &semester).await?;
+
+    Ok(SearchResults::new(bytes.into())?.model()?)
+}
+
+/// Fetch the schedules of many queries concurrently over the specified session.
+///
+/// At most `concurrency` queries are fetched at a time, and each result is yielded as it
+/// completes, with all pages of the schedule merged into a single model. The session only needs
+/// a [`Token`](session::Token), as it is initialized for the semester of each query as needed.
+///
+/// Because the host keeps a single form state per session, each concurrent query after the first
+/// is fetched over its own [`Session::fork`](Session::fork), so that queries spanning multiple
+/// pages don't replace each other's form state. Forks are only made once there is a query for
+/// them, thus at most `concurrency - 1` additional tokens are acquired, and a `concurrency` of 1
+/// fetches every query over the specified session. Queries are fetched grouped by semester, and
+/// each session is initialized again whenever the semester of its next query differs.
+pub fn fetch_queries<T, I>(
+    session: &Session<T>,
+    queries: I,
+    concurrency: usize,
+) -> impl Stream<Item = Result<(Course, ClassScheduleModel), ScheduleError>>
+where
+    T: Transport,
+    I: IntoIterator<Item = Query>,
+{
+    let mut queries = queries.into_iter().collect::<VecDeque<_>>();
+    queries
+        .make_contiguous()
+        .sort_by_key(|query| *query.semester());
+    let queries = Arc::new(Mutex::new(queries));
+    let session = session.clone();
+
+    stream::iter(0..concurrency.max(1))
+        .map(move |worker| {
+            let worker = Worker {
+                session: (worker == 0).then(|| session.clone()),
+                semester: None,
+                parent: session.clone(),
+                queries: queries.clone(),
+            };
+            Box::pin(stream::unfold(worker, |mut worker| async move {
+                let query = worker.queries.lock().unwrap().pop_front()?;
+                let result = worker.fetch(query).await;
+                Some((result, worker))
+            }))
+        })
+        .flatten_unordered(None)
+}
+
+/// Fetches one query at a time for [`fetch_queries`](fetch_queries).
+struct Worker<T> {
+    // Forked from the parent once there is a query to fetch, except for the first worker.
+    session: Option<Session<T>>,
+    // Semester the session was last initialized for.
+    semester: Option<Semester>,
+    parent: Session<T>,
+    queries: Arc<Mutex<VecDeque<Query>>>,
+}
+
+impl<T> Worker<T>
+where
+    T: Transport,
+{
+    /// Fetch the query, forking or initializing the session beforehand if needed.
+    async fn fetch(&mut self, query: Query) -> Result<(Course, ClassScheduleModel), ScheduleError> {
+        let session = match self.session.take() {
+            Some(session) => session,
+            None => {
+                let session = self.parent.fork(query.semester()).await?;
+                self.semester = Some(*query.semester());
+                session
+            }
+        };
+        let session = self.session.insert(session);
+
+        if self.semester != Some(*query.semester()) {
+            session.initialize(query.semester()).await?;
+            self.semester = Some(*query.semester());
+        }
+
+        let course = query.course().clone();
+        Ok((course, schedule_model(session, query).await?))
+    }
+}
+
+/// Fetch every page of the query and merge them into a single model.
+async fn schedule_model<T>(
+    session: &Session<T>,
+    query: Query,
+) -> Result<ClassScheduleModel, ScheduleError>
+where
+    T: Transport,
+{
+    let mut pages = Box::pin(session.schedule_iter(query.clone()));
+    let mut model: Option<ClassScheduleModel> = None;
+    while let Some(page) = pages.try_next().await? {
+        let page = ClassSchedule::from_page(page)?.model()?;
+        model = Some(match model {
+            // Keep the time the first page was fetched
+            Some(mut model) => {
+                model.groups.extend(page.groups);
+                ClassScheduleModel {
+                    groups: model.groups,
+                    fetched_at: model.fetched_at,
+                    ..page
+                }
+            }
+            None => page,
+        });
+    }
+
+    // Every query yields at least one page
+    Ok(model
+        .ok_or(ParseError::UnknownHtmlFormat)?
+        .with_query(&query))
+}
+
+/// Construct a session to the live host, initialized for the specified semester.
+#[cfg(feature = "rustls")]
+async fn session(
+    semester: &Semester,
+) -> Result<Session<Client<hyper_rustls::HttpsConnector<HttpConnector>>>, SessionError> {
+    let client = client();
+    let token = Token::new(&client).await?;
+
+    let session = Session::new(client, token);
+    session.initialize(semester).await?;
+
+    Ok(session)
+}
+
+/// Construct a client to the live host.
+#[cfg(feature = "rustls")]
+fn client() -> Client<hyper_rustls::HttpsConnector<HttpConnector>> {
+    Client::builder().build(
         hyper_rustls::HttpsConnectorBuilder::new()
             .with_native_roots()
             .https_only()
             .enable_http1()
             .build(),
-    );
-    let token = Token::new(&client).await?;
-
-    let session = Session::new(client, token);
-    session.initialize(&semester).await?;
-
-    Ok(session
-        .schedule_iter(Query::new(course, semester, career))
-        .map_ok(|bytes| ClassSchedule::new(bytes.into())))
+    )
 }
 
 /// Error when iterating schedules.
@@ -1,21 +1,64 @@
 //! Models of parser structs with all fields evaluated.
 
-use chrono::{NaiveDate, NaiveTime};
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
 #[cfg(feature = "serde_support")]
 use serde::{Deserialize, Serialize};
 
-use crate::parser::{Class, ClassGroup, ClassSchedule, ClassType, DayOfWeek, ParseError};
+use crate::{
+    parser::{
+        Class, ClassGroup, ClassSchedule, ClassType, DayOfWeek, Instructor, ParseError,
+        SearchResult, SeatStatus,
+    },
+    session::Query,
+    Career, Course, Credits, Semester,
+};
 
 // TODO: document models
 
 /// Model of a [`ClassSchedule`](ClassSchedule) with all fields evaluated.
+///
+/// The course and career aren't included in the schedule itself, thus they are only known once
+/// the model is associated with its query via
+/// [`ClassScheduleModel::with_query`](ClassScheduleModel::with_query).
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
 pub struct ClassScheduleModel {
-    // TODO: add semester?
+    /// Internal id of the course, such as `004544`.
+    pub course_id: Option<String>,
+    /// Name of the course, such as `CSE 115 LLR`, if it's in the catalog.
+    pub course_name: Option<String>,
+    pub semester: Option<Semester>,
+    pub career: Option<Career>,
+    /// Page number of the schedule, starting from 1.
+    ///
+    /// Schedules merged from every page, such as those from [`fetch_many`](crate::fetch_many),
+    /// have the page number of the last page.
+    pub page_num: u32,
+    pub total_pages: u32,
+    /// Time the page was received from the host, or `None` if it was parsed from raw bytes.
+    pub fetched_at: Option<DateTime<Utc>>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub units: Option<Credits>,
+    pub grading_basis: Option<String>,
+    /// Enrollment requirements of the course, such as its prerequisites.
+    pub requirements: Option<String>,
     pub groups: Vec<ClassGroupModel>,
 }
 
+impl ClassScheduleModel {
+    /// Associate the model with the query it was fetched for, filling in the course, semester,
+    /// and career.
+    pub fn with_query(mut self, query: &Query) -> Self {
+        let course = query.course();
+        self.course_id = Some(course.id().to_owned());
+        self.course_name = course.subject().map(|_| course.to_string());
+        self.semester = Some(*query.semester());
+        self.career = Some(query.career().clone());
+        self
+    }
+}
+
 /// Model of a [`ClassGroup`](ClassGroup) with all fields evaluated.
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
@@ -38,9 +81,32 @@ pub struct ClassModel {
     pub start_time: Option<NaiveTime>,
     pub end_time: Option<NaiveTime>,
     pub room: Option<String>,
-    pub instructor: Option<String>,
+    pub instructor: Option<Instructor>,
     pub open_seats: Option<u32>,
     pub total_seats: Option<u32>,
+    pub seat_status: Option<SeatStatus>,
+}
+
+/// Model of a [`SearchResult`](SearchResult) with all fields evaluated.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub struct SearchResultModel {
+    pub course_id: String,
+    pub subject: String,
+    pub catalog_number: String,
+    pub title: String,
+}
+
+impl SearchResultModel {
+    /// Get the course matching the search result, for use in a [`Query`](crate::session::Query).
+    pub fn course(&self) -> Course {
+        Course::Raw(self.course_id.clone())
+    }
+
+    /// Infer the career offering the course, see [`Career::infer`](Career::infer).
+    pub fn career(&self) -> Option<Career> {
+        Career::infer_from_name(&format!("{}{}", self.subject, self.catalog_number))
+    }
 }
 
 impl TryFrom<&ClassSchedule> for ClassScheduleModel {
@@ -52,7 +118,24 @@ impl TryFrom<&ClassSchedule> for ClassScheduleModel {
             groups.push(ClassGroupModel::try_from(&group)?);
         }
 
-        Ok(ClassScheduleModel { groups })
+        Ok(ClassScheduleModel {
+            course_id: None,
+            course_name: None,
+            semester: schedule.semester().ok(),
+            career: None,
+            page_num: schedule.page_num()?,
+            total_pages: schedule.total_pages()?,
+            fetched_at: schedule.fetched_at(),
+            title: schedule.title().ok().map(|title| title.to_owned()),
+            description: schedule.description().ok(),
+            units: schedule.units().ok(),
+            grading_basis: schedule
+                .grading_basis()
+                .ok()
+                .map(|grading_basis| grading_basis.to_owned()),
+            requirements: schedule.requirements().ok(),
+            groups,
+        })
     }
 }
 
@@ -78,8 +161,10 @@ impl TryFrom<&Class<'_>> for ClassModel {
     type Error = ParseError;
 
     fn try_from(class: &Class<'_>) -> Result<Self, Self::Error> {
+        // Every field about seats is derived from the same status
+        let seat_status = class.seat_status().ok();
         Ok(ClassModel {
-            is_open: class.is_open().ok(),
+            is_open: seat_status.as_ref().map(SeatStatus::is_open),
             class_type: class.class_type().ok(),
             class_id: class.class_id().ok(),
             section: class.section().ok().map(ToOwned::to_owned),
@@ -91,9 +176,23 @@ impl TryFrom<&Class<'_>> for ClassModel {
             start_time: class.start_time()?,
             end_time: class.end_time()?,
             room: class.room().ok().map(ToOwned::to_owned),
-            instructor: class.instructor().ok().map(ToOwned::to_owned),
-            open_seats: class.open_seats().ok().flatten(),
-            total_seats: class.total_seats().ok().flatten(),
+            instructor: class.instructor().ok(),
+            open_seats: seat_status.as_ref().and_then(SeatStatus::open_seats),
+            total_seats: seat_status.as_ref().and_then(SeatStatus::total_seats),
+            seat_status,
+        })
+    }
+}
+
+impl TryFrom<&SearchResult<'_>> for SearchResultModel {
+    type Error = ParseError;
+
+    fn try_from(result: &SearchResult<'_>) -> Result<Self, Self::Error> {
+        Ok(SearchResultModel {
+            course_id: result.course_id()?.to_owned(),
+            subject: result.subject()?.to_owned(),
+            catalog_number: result.catalog_number()?.to_owned(),
+            title: result.title()?.to_owned(),
         })
     }
 }
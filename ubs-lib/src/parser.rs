@@ -1,8 +1,8 @@
 //! Low-level access to the schedule parser.
 
-use std::{borrow::Cow, fmt::Display, str::FromStr};
+use std::{borrow::Cow, collections::HashMap, fmt::Display, str::FromStr, sync::LazyLock};
 
-use chrono::{NaiveDate, NaiveTime};
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
 use regex::Regex;
 #[cfg(feature = "serde_support")]
 use serde::{Deserialize, Serialize};
@@ -10,44 +10,55 @@ use thiserror::Error;
 use tl::{Node, ParserOptions, VDom, VDomGuard};
 
 use crate::{
-    model::{ClassGroupModel, ClassModel, ClassScheduleModel},
-    ParseIdError, Semester,
+    model::{ClassGroupModel, ClassModel, ClassScheduleModel, SearchResultModel},
+    session::Page,
+    Course, Credits, ParseIdError, Semester,
 };
 
 const CLASSES_PER_PAGE: u32 = 50;
-const CLASSES_PER_GROUP: u32 = 3;
 
 // Rust does macro expansion before resolving consts, thus I cannot embed `{}` directly
 // in consts and use the `format!` macro. Defining declarative macros via `macro_rules!` is an
 // alternative to get around this limitation.
 
+macro_rules! SID_TAG {
+    () => {
+        "ICSID"
+    };
+}
 macro_rules! SEMESTER_TAG {
     () => {
         "TERM_VAL_TBL_DESCR"
     };
 }
 // First is the total amount of classes for the current page (max 50)
-const NUM_INFO_FORMAT_1: &str = r"^(\d+) option$";
+static NUM_INFO_FORMAT_1: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(\d+) option$").unwrap());
 // First is the total amount of class groups viewed (max second parameter, increments by 50 per page)
 // Second is the total amount of class groups available to view
-const NUM_INFO_FORMAT_2: &str = r"^1 - (\d+) of (\d+) options$";
+static NUM_INFO_FORMAT_2: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^1 - (\d+) of (\d+) options$").unwrap());
 macro_rules! NUM_INFO_TAG {
     () => {
         "SSR_CLSRCH_F_WK_SSR_MSG_TEXT"
     };
 }
 // First is the class group index ((page * 50) - 1)
-const SESSION_FORMAT: &str = r"^University (\d\d?) Week Session$";
+static SESSION_FORMAT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^University (\d\d?) Week Session$").unwrap());
 macro_rules! SESSION_TAG {
     () => {
         "SSR_DER_CS_GRP_SESSION_CODE$215$${}"
     };
 }
-// First is class index in group (1-3)
-// Second is (294, 295, 296) depending on class index in group (1-3)
+// First is class index in group (1-n)
+// Second is a sequence number depending on class index in group (e.g. 294, 295, 296), see `TagIndex`
 // Third is the class group index ((page * 50) - 1)
-const CLASS_ID_FORMAT: &str = r"^Class Nbr (\d+) - Section ([A-Z](?:\d?)+) ([A-Z]+)$";
-const CLASS_ID_TAG_SEQ: [u32; 3] = [294, 295, 296];
+static CLASS_ID_FORMAT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^Class Nbr (\d+) - Section ([A-Z](?:\d?)+) ([A-Z]+)$").unwrap());
+static CLASS_ID_TAG_FORMAT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^SSR_CLSRCH_F_WK_SSR_CMPNT_DESCR_(\d+)\$(\d+)\$\$(\d+)$").unwrap()
+});
 macro_rules! CLASS_ID_TAG {
     () => {
         "SSR_CLSRCH_F_WK_SSR_CMPNT_DESCR_{}${}$${}"
@@ -60,48 +71,119 @@ macro_rules! DATES_TAG {
         "SSR_CLSRCH_F_WK_SSR_MTG_DT_LONG_1$88$${}"
     };
 }
-// First is class index in group (1-3)
-// Second is (134, 135, 154) depending on class index in group (1-3)
+// First is class index in group (1-n)
+// Second is a sequence number depending on class index in group (e.g. 134, 135, 154), see `TagIndex`
 // Third is the class group index ((page * 50) - 1)
 const DATETIME_TIME_FORMAT: &str = "%-I:%M%p";
-const DATETIME_FORMAT: &str =
-    r"^((?:[A-Z][a-z]+\s)+)(\d?\d:\d\d(?:AM|PM)) to (\d?\d:\d\d(?:AM|PM))$";
-const DATETIME_TAG_SEQ: [u32; 3] = [134, 135, 154];
+static DATETIME_FORMAT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^((?:[A-Z][a-z]+\s)+)(\d?\d:\d\d(?:AM|PM)) to (\d?\d:\d\d(?:AM|PM))$").unwrap()
+});
+static DATETIME_TAG_FORMAT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^SSR_CLSRCH_F_WK_SSR_MTG_SCHED_L_(\d+)\$(\d+)\$\$(\d+)$").unwrap()
+});
 macro_rules! DATETIME_TAG {
     () => {
         "SSR_CLSRCH_F_WK_SSR_MTG_SCHED_L_{}${}$${}"
     };
 }
-// First is class index in group (1-3)
+// First is class index in group (1-n)
 // Second is the class group index ((page * 50) - 1)
 macro_rules! ROOM_TAG {
     () => {
         "SSR_CLSRCH_F_WK_SSR_MTG_LOC_LONG_{}${}"
     };
 }
-// First is class index in group (1-3)
-// Second is (86, 161, 162) depending on class index in group (1-3)
+// First is class index in group (1-n)
+// Second is a sequence number depending on class index in group (e.g. 86, 161, 162), see `TagIndex`
 // Third is the class group index ((page * 50) - 1)
-const INSTRUCTOR_TAG_SEQ: [u32; 3] = [86, 161, 162];
+static INSTRUCTOR_TAG_FORMAT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^SSR_CLSRCH_F_WK_SSR_INSTR_LONG_(\d+)\$(\d+)\$\$(\d+)$").unwrap()
+});
 macro_rules! INSTRUCTOR_TAG {
     () => {
         "SSR_CLSRCH_F_WK_SSR_INSTR_LONG_{}${}$${}"
     };
 }
-// First is class index in group (1-3)
+// Either "Closed", reserved (e.g. Reserved Seats), or open seats/waitlisted students followed by
+// the total (e.g. Open Seats 5 of 30 or Wait List 3 of 10)
+static SEATS_FORMAT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^Open Seats (\d+) of (\d+)$").unwrap());
+static WAITLIST_FORMAT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^Wait ?[Ll]ist (\d+) of (\d+)$").unwrap());
+// First is class index in group (1-n)
 // Second is the class group index ((page * 50) - 1)
-const SEATS_FORMAT: &str = r"^Open Seats (\d+) of (\d+)$";
 macro_rules! SEATS_TAG {
     () => {
         "SSR_CLSRCH_F_WK_SSR_DESCR50_{}${}"
     };
 }
+// Course details on the same page as the schedule (SSR_CRSE_INFO_FL). None of the detail tags have
+// been verified against a page recorded from the host, as the tests only parse HTML generated from
+// these same ids.
+macro_rules! TITLE_TAG {
+    () => {
+        "SSR_CRSE_INFO_V_COURSE_TITLE_LONG"
+    };
+}
+// May contain sub-nodes, such as links
+macro_rules! DESCRIPTION_TAG {
+    () => {
+        "SSR_CRSE_INFO_V_DESCRLONG"
+    };
+}
+// First is the units, either a number or a range (e.g. 4 or 1 - 3)
+static UNITS_FORMAT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(\d+(?:\.\d+)?(?:\s*-\s*\d+(?:\.\d+)?)?) [Uu]nits?$").unwrap());
+macro_rules! UNITS_TAG {
+    () => {
+        "SSR_CRSE_INFO_V_SSR_UNITS_RANGE"
+    };
+}
+// Grading basis (e.g. Graded, Satisfactory/Unsatisfactory)
+macro_rules! GRADING_BASIS_TAG {
+    () => {
+        "SSR_CRSE_INFO_V_SSR_GRADE_BASIS"
+    };
+}
+// May contain sub-nodes, such as links to prerequisite courses
+macro_rules! REQUIREMENTS_TAG {
+    () => {
+        "SSR_CRSE_INFO_V_SSR_REQUISITE_LONG"
+    };
+}
+// Each option is a semester, with its id as the value and its name as the text. Not yet verified
+// against a page recorded from the host, as the tests only parse HTML generated from this id.
+macro_rules! SEMESTER_SELECT_TAG {
+    () => {
+        "CLASS_SRCH_WRK2_STRM$35$"
+    };
+}
+// Neither search tag has been verified against a response recorded from the host, as the tests
+// only parse HTML generated from these same ids.
+// First is the result index (0-n)
+macro_rules! SEARCH_COURSE_ID_TAG {
+    () => {
+        "SSR_CLSRCH_ES_FL_CRSE_ID${}"
+    };
+}
+// First is the subject (e.g. CSE)
+// Second is the catalog number (e.g. 115)
+// Third is the title (e.g. Introduction to Computer Science I)
+static SEARCH_DESCR_FORMAT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^([A-Z]+)\s+(\w+) - (.+)$").unwrap());
+// First is the result index (0-n)
+macro_rules! SEARCH_DESCR_TAG {
+    () => {
+        "SSR_CLSRCH_ES_FL_SSR_CRSE_DESCR${}"
+    };
+}
 
-// TODO: I can supply more information, like class description, units, etc.
 /// Parser for raw class schedule data.
 #[derive(Debug)]
 pub struct ClassSchedule {
     dom: VDomGuard,
+    index: TagIndex,
+    fetched_at: Option<DateTime<Utc>>,
 }
 
 impl ClassSchedule {
@@ -109,8 +191,27 @@ impl ClassSchedule {
     pub fn new(bytes: Vec<u8>) -> Result<Self, ParseError> {
         // TODO: consider enabling tracking for perf
         let dom = unsafe { tl::parse_owned(String::from_utf8(bytes)?, ParserOptions::default())? };
+        let index = TagIndex::new(dom.get_ref());
 
-        Ok(Self { dom })
+        Ok(Self {
+            dom,
+            index,
+            fetched_at: None,
+        })
+    }
+
+    /// Construct a new [`ClassSchedule`](ClassSchedule) from a page fetched by a
+    /// [`Session`](crate::session::Session), keeping the time it was fetched.
+    pub fn from_page(page: Page) -> Result<Self, ParseError> {
+        let fetched_at = page.fetched_at;
+        let mut schedule = Self::new(page.into())?;
+        schedule.fetched_at = Some(fetched_at);
+        Ok(schedule)
+    }
+
+    /// Time the page was fetched, or `None` if the schedule was constructed from raw bytes.
+    pub fn fetched_at(&self) -> Option<DateTime<Utc>> {
+        self.fetched_at
     }
 
     /// Return a model of the class schedule with all fields evaluated.
@@ -119,9 +220,10 @@ impl ClassSchedule {
     }
 
     /// Get a group from its index.
-    pub fn group_from_index(&self, index: u32) -> ClassGroup {
+    pub fn group_from_index(&self, index: u32) -> ClassGroup<'_> {
         ClassGroup {
             dom: self.dom.get_ref(),
+            index: &self.index,
             group_num: index,
         }
     }
@@ -133,14 +235,69 @@ impl ClassSchedule {
             .map_err(|err| err.into())
     }
 
+    /// Get the title of the course, such as `Introduction to Computer Science I`.
+    ///
+    /// Note that the element ids of the course details, including the title, description, units,
+    /// grading basis and requirements, haven't been verified against a page recorded from the
+    /// host, thus they may return [`ParseError::MissingTag`](ParseError::MissingTag) if they
+    /// differ.
+    pub fn title(&self) -> Result<&str, ParseError> {
+        get_text_from_id_without_sub_nodes(self.dom.get_ref(), TITLE_TAG!())
+            .map(|title| title.trim())
+    }
+
+    /// Get the description of the course.
+    pub fn description(&self) -> Result<String, ParseError> {
+        get_text_from_id(self.dom.get_ref(), DESCRIPTION_TAG!())
+    }
+
+    /// Get the credit units of the course.
+    pub fn units(&self) -> Result<Credits, ParseError> {
+        let units = get_text_from_id_without_sub_nodes(self.dom.get_ref(), UNITS_TAG!())?;
+        let units = UNITS_FORMAT
+            .captures(units.trim())
+            .ok_or(ParseError::UnknownElementFormat)?;
+
+        units
+            .get(1)
+            .ok_or(ParseError::UnknownElementFormat)?
+            .as_str()
+            .parse()
+            .map_err(|_| ParseError::UnknownElementFormat)
+    }
+
+    /// Get the grading basis of the course, such as `Graded`.
+    pub fn grading_basis(&self) -> Result<&str, ParseError> {
+        get_text_from_id_without_sub_nodes(self.dom.get_ref(), GRADING_BASIS_TAG!())
+            .map(|grading_basis| grading_basis.trim())
+    }
+
+    /// Get the enrollment requirements of the course, such as its prerequisites.
+    ///
+    /// Courses without requirements return [`ParseError::MissingTag`](ParseError::MissingTag).
+    pub fn requirements(&self) -> Result<String, ParseError> {
+        get_text_from_id(self.dom.get_ref(), REQUIREMENTS_TAG!())
+    }
+
+    /// Get the total amount of pages for the query.
     pub fn total_pages(&self) -> Result<u32, ParseError> {
         Ok(self.calc_page(self.total()?.1))
     }
 
+    /// Get the current page number, starting from 1.
     pub fn page_num(&self) -> Result<u32, ParseError> {
         Ok(self.calc_page(self.total()?.0))
     }
 
+    /// Get the unique identifier of the form, required to request subsequent pages.
+    pub(crate) fn sid(&self) -> Result<String, ParseError> {
+        get_node_from_id(self.dom.get_ref(), SID_TAG!())?
+            .as_tag()
+            .and_then(|tag| tag.attributes().get("value").flatten())
+            .map(|value| value.as_utf8_str().into_owned())
+            .ok_or(ParseError::UnknownHtmlFormat)
+    }
+
     /// Iterator over groups of classes.
     ///
     /// In the catalog, classes are grouped in sets of 3 (usually)
@@ -149,12 +306,13 @@ impl ClassSchedule {
         let total = self.total()?;
 
         // Every page contains the bytes of the previous pages
-        let first_class_index = total.1 - (total.1 % 50);
+        let first_class_index = (self.calc_page(total.0).max(1) - 1) * CLASSES_PER_PAGE;
         let last_class_index = total.0;
 
         Ok(
             (first_class_index..last_class_index).map(|group_num| ClassGroup {
                 dom: self.dom.get_ref(),
+                index: &self.index,
                 group_num,
             }),
         )
@@ -162,14 +320,13 @@ impl ClassSchedule {
 
     #[inline]
     fn calc_page(&self, groups: u32) -> u32 {
-        // TODO: https://doc.rust-lang.org/std/primitive.u32.html#method.div_ceil
-        (groups as f32 / CLASSES_PER_PAGE as f32).ceil() as u32
+        groups.div_ceil(CLASSES_PER_PAGE)
     }
 
     // (groups viewed, total groups)
     fn total(&self) -> Result<(u32, u32), ParseError> {
         let info = get_text_from_id_without_sub_nodes(self.dom.get_ref(), NUM_INFO_TAG!())?;
-        match Regex::new(NUM_INFO_FORMAT_1).unwrap().captures(info) {
+        match NUM_INFO_FORMAT_1.captures(info) {
             Some(captures) => {
                 let groups = captures
                     .get(1)
@@ -180,7 +337,7 @@ impl ClassSchedule {
 
                 Ok((groups, groups))
             }
-            None => match Regex::new(NUM_INFO_FORMAT_2).unwrap().captures(info) {
+            None => match NUM_INFO_FORMAT_2.captures(info) {
                 Some(captures) => Ok((
                     captures
                         .get(1)
@@ -206,6 +363,7 @@ impl ClassSchedule {
 #[derive(Debug, Clone, Copy)]
 pub struct ClassGroup<'a> {
     dom: &'a VDom<'a>,
+    index: &'a TagIndex,
     group_num: u32,
 }
 
@@ -220,16 +378,24 @@ impl<'a> ClassGroup<'a> {
     pub fn class_from_index(&self, index: u32) -> Class<'a> {
         Class {
             dom: self.dom,
+            index: self.index,
             class_num: index,
             group_num: self.group_num,
         }
     }
 
+    /// Get the amount of classes in the group.
+    ///
+    /// Most groups contain 3 classes, though it can be anywhere from 1 to many.
+    pub fn num_classes(&self) -> u32 {
+        self.index.num_classes(self.group_num)
+    }
+
     /// Iterator over classes in group.
     pub fn class_iter(&self) -> impl Iterator<Item = Class<'a>> + '_ {
-        // TODO: not every class will be in a group of 3
-        (0..CLASSES_PER_GROUP).map(|class_num| Class {
+        (0..self.num_classes()).map(|class_num| Class {
             dom: self.dom,
+            index: self.index,
             class_num,
             group_num: self.group_num,
         })
@@ -242,8 +408,7 @@ impl<'a> ClassGroup<'a> {
     pub fn session(&self) -> Result<u32, ParseError> {
         let session =
             get_text_from_id_without_sub_nodes(self.dom, &format!(SESSION_TAG!(), self.group_num))?;
-        let re = Regex::new(SESSION_FORMAT)
-            .unwrap()
+        let re = SESSION_FORMAT
             .captures(session)
             .ok_or(ParseError::UnknownElementFormat)?;
         re.get(1)
@@ -290,6 +455,7 @@ impl<'a> ClassGroup<'a> {
 #[derive(Debug, Clone, Copy)]
 pub struct Class<'a> {
     dom: &'a VDom<'a>,
+    index: &'a TagIndex,
     class_num: u32,
     group_num: u32,
 }
@@ -301,17 +467,11 @@ impl Class<'_> {
     }
 
     /// Get if the class is open or closed.
+    ///
+    /// A class is only open if it is [`SeatStatus::Open`](SeatStatus::Open). Classes with
+    /// reserved seats are considered closed, as they aren't open to every student.
     pub fn is_open(&self) -> Result<bool, ParseError> {
-        let seats = get_text_from_id_without_sub_nodes(
-            self.dom,
-            &format!(SEATS_TAG!(), self.class_num + 1, self.group_num),
-        )?;
-
-        if seats == "Closed" {
-            return Ok(false);
-        }
-
-        Ok(true)
+        Ok(self.seat_status()?.is_open())
     }
 
     /// Get the type of class.
@@ -392,39 +552,62 @@ impl Class<'_> {
         )
     }
 
-    // TODO: specific error if the class says "To be Announced"
-    /// Get the name of the instructor.
+    /// Get the instructor for this class.
     ///
-    /// Note that sometimes the instructor doesn't exist and is labeled as
-    /// `To be Announced`. In that case, the function will error.
-    pub fn instructor(&self) -> Result<&str, ParseError> {
-        // Not much I can do in terms of validation. Some people have very unique patterns in their
-        // names.
-        get_text_from_id_without_sub_nodes(
+    /// Co-taught classes have multiple instructors, and classes whose instructor is yet to be
+    /// decided are [`Instructor::Tba`](Instructor::Tba).
+    pub fn instructor(&self) -> Result<Instructor, ParseError> {
+        get_lines_from_id(
             self.dom,
             &format!(
                 INSTRUCTOR_TAG!(),
                 self.class_num + 1,
-                INSTRUCTOR_TAG_SEQ[self.class_num as usize],
+                self.index
+                    .instructor_seq(self.group_num, self.class_num)
+                    .ok_or_else(|| self.missing_tag("SSR_CLSRCH_F_WK_SSR_INSTR_LONG"))?,
                 self.group_num
             ),
-        )
+        )?
+        .join("\n")
+        .parse()
+    }
+
+    /// Get the status of the seats for this class.
+    pub fn seat_status(&self) -> Result<SeatStatus, ParseError> {
+        let seats = get_text_from_id_without_sub_nodes(
+            self.dom,
+            &format!(SEATS_TAG!(), self.class_num + 1, self.group_num),
+        )?
+        .trim();
+
+        if seats == "Closed" {
+            return Ok(SeatStatus::Closed);
+        }
+        if seats.starts_with("Reserved") {
+            return Ok(SeatStatus::Reserved);
+        }
+        if let Some((open, total)) = parse_seats(&SEATS_FORMAT, seats)? {
+            return Ok(SeatStatus::Open { open, total });
+        }
+        if let Some((taken, capacity)) = parse_seats(&WAITLIST_FORMAT, seats)? {
+            return Ok(SeatStatus::Waitlist { taken, capacity });
+        }
+
+        Ok(SeatStatus::Unknown(seats.to_owned()))
     }
 
-    // TODO: specific error for closed class
     /// Get the open seats for this class.
     ///
-    /// Note that if the class is closed this function will error.
+    /// Returns `None` if the class isn't [`SeatStatus::Open`](SeatStatus::Open).
     pub fn open_seats(&self) -> Result<Option<u32>, ParseError> {
-        self.seats().map(|seats| seats.map(|seats| seats.0))
+        Ok(self.seat_status()?.open_seats())
     }
 
-    // TODO: ^
     /// Get the total seats for this class.
     ///
-    /// Note that if the class is closed this function will error.
+    /// Returns `None` if the class isn't [`SeatStatus::Open`](SeatStatus::Open).
     pub fn total_seats(&self) -> Result<Option<u32>, ParseError> {
-        self.seats().map(|seats| seats.map(|seats| seats.1))
+        Ok(self.seat_status()?.total_seats())
     }
 
     /// Get various bits of information for this class in the form,
@@ -435,13 +618,14 @@ impl Class<'_> {
             &format!(
                 CLASS_ID_TAG!(),
                 self.class_num + 1,
-                CLASS_ID_TAG_SEQ[self.class_num as usize],
+                self.index
+                    .class_id_seq(self.group_num, self.class_num)
+                    .ok_or_else(|| self.missing_tag("SSR_CLSRCH_F_WK_SSR_CMPNT_DESCR"))?,
                 self.group_num
             ),
         )?;
 
-        let re = Regex::new(CLASS_ID_FORMAT)
-            .unwrap()
+        let re = CLASS_ID_FORMAT
             .captures(class_info)
             .ok_or(ParseError::UnknownElementFormat)?;
         Ok((
@@ -459,7 +643,9 @@ impl Class<'_> {
             &format!(
                 DATETIME_TAG!(),
                 self.class_num + 1,
-                DATETIME_TAG_SEQ[self.class_num as usize],
+                self.index
+                    .datetime_seq(self.group_num, self.class_num)
+                    .ok_or_else(|| self.missing_tag("SSR_CLSRCH_F_WK_SSR_MTG_SCHED_L"))?,
                 self.group_num
             ),
         )
@@ -470,8 +656,7 @@ impl Class<'_> {
             match node.inner_text(self.dom.parser()) {
                 Cow::Borrowed(_) => Err(ParseError::UnknownHtmlFormat),
                 Cow::Owned(value) => {
-                    let re = Regex::new(DATETIME_FORMAT)
-                        .unwrap()
+                    let re = DATETIME_FORMAT
                         .captures(&value)
                         .ok_or(ParseError::UnknownElementFormat)?;
 
@@ -496,40 +681,182 @@ impl Class<'_> {
         })?
     }
 
-    /// Get various bits of information for this class seats in the form,
-    /// `(days_of_weeek, start_time, end_time)`.
-    // TODO: return enum instead of option
-    fn seats(&self) -> Result<Option<(u32, u32)>, ParseError> {
-        let seats = get_text_from_id_without_sub_nodes(
-            self.dom,
-            &format!(SEATS_TAG!(), self.class_num + 1, self.group_num),
-        )?;
+    /// Error for a tag whose sequence number could not be discovered.
+    fn missing_tag(&self, prefix: &str) -> ParseError {
+        ParseError::MissingTag {
+            tag: format!("{}_{}$?$${}", prefix, self.class_num + 1, self.group_num),
+        }
+    }
+}
 
-        match seats {
-            "Closed" => Ok(None),
-            _ => {
-                let re = Regex::new(SEATS_FORMAT)
-                    .unwrap()
-                    .captures(seats)
-                    .ok_or(ParseError::UnknownElementFormat)?;
+/// Status of the seats of a class.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub enum SeatStatus {
+    /// Class has seats available.
+    Open { open: u32, total: u32 },
+    /// Class is full without a waitlist.
+    Closed,
+    /// Class is full, though students can join the waitlist.
+    Waitlist { taken: u32, capacity: u32 },
+    /// Seats are reserved for specific students, such as those in a major.
+    Reserved,
+    /// Status is in an unknown format, containing the raw text.
+    Unknown(String),
+}
 
-                Ok(Some((
-                    re.get(1)
-                        .ok_or(ParseError::UnknownElementFormat)?
-                        .as_str()
-                        .parse()
-                        .map_err(|_| ParseError::UnknownElementFormat)?, // Open seats
-                    re.get(2)
-                        .ok_or(ParseError::UnknownElementFormat)?
-                        .as_str()
-                        .parse()
-                        .map_err(|_| ParseError::UnknownElementFormat)?, // Total seats
-                )))
+impl SeatStatus {
+    /// Whether the class has seats available.
+    pub fn is_open(&self) -> bool {
+        matches!(self, SeatStatus::Open { .. })
+    }
+
+    /// Get the open seats, or `None` if the class isn't [`SeatStatus::Open`](SeatStatus::Open).
+    pub fn open_seats(&self) -> Option<u32> {
+        match self {
+            SeatStatus::Open { open, .. } => Some(*open),
+            _ => None,
+        }
+    }
+
+    /// Get the total seats, or `None` if the class isn't [`SeatStatus::Open`](SeatStatus::Open).
+    pub fn total_seats(&self) -> Option<u32> {
+        match self {
+            SeatStatus::Open { total, .. } => Some(*total),
+            _ => None,
+        }
+    }
+}
+
+/// Instructor of a class.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub enum Instructor {
+    /// Instructor is yet to be announced.
+    Tba,
+    /// Names of the instructors, of which there are multiple if the class is co-taught.
+    Named(Vec<InstructorName>),
+}
+
+impl Instructor {
+    /// Get the names of the instructors, which is empty if the instructor is yet to be announced.
+    pub fn names(&self) -> &[InstructorName] {
+        match self {
+            Instructor::Tba => &[],
+            Instructor::Named(names) => names,
+        }
+    }
+
+    /// Get if the instructor is yet to be announced.
+    pub fn is_tba(&self) -> bool {
+        matches!(self, Instructor::Tba)
+    }
+}
+
+impl FromStr for Instructor {
+    type Err = ParseError;
+
+    /// Parse instructors separated by lines or semicolons, where each line is either in the form
+    /// `Last,First` (e.g. `Doe,Jane, Smith,John`), `Last, First`, or `First Last` (e.g.
+    /// `Jane Doe, John Smith`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let text = s.trim();
+        if text.eq_ignore_ascii_case("To be Announced") || text.eq_ignore_ascii_case("TBA") {
+            return Ok(Instructor::Tba);
+        }
+
+        let mut names = Vec::new();
+        for line in text.split(['\n', ';']).map(str::trim) {
+            let parts: Vec<_> = line
+                .split(',')
+                .map(str::trim)
+                .filter(|part| !part.is_empty())
+                .collect();
+
+            // A comma directly followed by a name is the `Last,First` format of the host
+            if line
+                .split(',')
+                .skip(1)
+                .any(|part| part.starts_with(|c: char| !c.is_whitespace()))
+            {
+                names.extend(line.split(", ").map(InstructorName::from_last_first));
+            } else if parts.len() == 2 && !parts.iter().all(|part| part.contains(' ')) {
+                names.push(InstructorName::from_last_first(line));
+            } else {
+                names.extend(parts.into_iter().map(InstructorName::from_first_last));
+            }
+        }
+
+        if names.is_empty() {
+            return Err(ParseError::UnknownElementFormat);
+        }
+        Ok(Instructor::Named(names))
+    }
+}
+
+impl Display for Instructor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Instructor::Tba => write!(f, "To be Announced"),
+            Instructor::Named(names) => {
+                for (i, name) in names.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{name}")?;
+                }
+                Ok(())
             }
         }
     }
 }
 
+/// Name of an instructor.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub struct InstructorName {
+    /// First name, which is empty if only one name is given.
+    pub first: String,
+    /// Last name.
+    pub last: String,
+}
+
+impl InstructorName {
+    /// Name in the form `Last, First`, such as `Doe,Jane`.
+    fn from_last_first(name: &str) -> Self {
+        match name.split_once(',') {
+            Some((last, first)) => Self::new(first, last),
+            None => Self::from_first_last(name),
+        }
+    }
+
+    /// Name in the form `First Last`, such as `Jane Doe`.
+    fn from_first_last(name: &str) -> Self {
+        match name.trim().rsplit_once(char::is_whitespace) {
+            Some((first, last)) => Self::new(first, last),
+            None => Self::new("", name),
+        }
+    }
+
+    fn new(first: &str, last: &str) -> Self {
+        let normalize = |name: &str| name.split_whitespace().collect::<Vec<_>>().join(" ");
+        Self {
+            first: normalize(first),
+            last: normalize(last),
+        }
+    }
+}
+
+impl Display for InstructorName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.first.is_empty() {
+            write!(f, "{}", self.last)
+        } else {
+            write!(f, "{} {}", self.first, self.last)
+        }
+    }
+}
+
 /// Type of class.
 #[derive(Debug, Clone, Copy)]
 #[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
@@ -569,6 +896,186 @@ impl Display for ClassType {
     }
 }
 
+/// Parser for the raw list of semesters offered by the host.
+///
+/// Note that the element id of the term dropdown hasn't been verified against a page recorded
+/// from the host, thus parsing may find no semesters if it differs.
+#[derive(Debug)]
+pub struct SemesterList {
+    dom: VDomGuard,
+}
+
+impl SemesterList {
+    /// Construct a new [`SemesterList`](SemesterList) with the specified bytes.
+    pub fn new(bytes: Vec<u8>) -> Result<Self, ParseError> {
+        let dom = unsafe { tl::parse_owned(String::from_utf8(bytes)?, ParserOptions::default())? };
+
+        Ok(Self { dom })
+    }
+
+    /// Get every semester in the form `(semester, name)`, in the order listed by the host.
+    ///
+    /// For instance, `(Semester::new(Season::Spring, 2024), "Spring 2024")`. Options with an
+    /// unknown term code are skipped, so that a single unexpected option doesn't prevent the
+    /// other semesters from being discovered.
+    pub fn semesters(&self) -> Result<Vec<(Semester, String)>, ParseError> {
+        let dom = self.dom.get_ref();
+        let select = get_node_from_id(dom, SEMESTER_SELECT_TAG!())?
+            .as_tag()
+            .ok_or(ParseError::UnknownHtmlFormat)?;
+
+        let mut semesters = Vec::new();
+        for option in select
+            .children()
+            .top()
+            .iter()
+            .filter_map(|handle| handle.get(dom.parser())?.as_tag())
+            .filter(|tag| tag.name() == "option")
+        {
+            let id = option
+                .attributes()
+                .get("value")
+                .flatten()
+                .map(|value| value.as_utf8_str().trim().to_owned())
+                .unwrap_or_default();
+            // The first option is usually a blank placeholder
+            if id.is_empty() {
+                continue;
+            }
+
+            let Ok(semester) = Semester::from_id(&id) else {
+                continue;
+            };
+            let name = option.inner_text(dom.parser()).trim().to_owned();
+            semesters.push((semester, name));
+        }
+
+        Ok(semesters)
+    }
+}
+
+/// Parser for raw course search results.
+///
+/// Note that the element ids of the results haven't been verified against a response recorded
+/// from the host, thus parsing may find no results if they differ.
+#[derive(Debug)]
+pub struct SearchResults {
+    dom: VDomGuard,
+}
+
+impl SearchResults {
+    /// Construct a new [`SearchResults`](SearchResults) with the specified bytes.
+    pub fn new(bytes: Vec<u8>) -> Result<Self, ParseError> {
+        let dom = unsafe { tl::parse_owned(String::from_utf8(bytes)?, ParserOptions::default())? };
+
+        Ok(Self { dom })
+    }
+
+    /// Return a model of every search result with all fields evaluated.
+    pub fn model(&self) -> Result<Vec<SearchResultModel>, ParseError> {
+        self.result_iter().map(|result| result.model()).collect()
+    }
+
+    /// Get a search result from its index.
+    pub fn result_from_index(&self, index: u32) -> SearchResult<'_> {
+        SearchResult {
+            dom: self.dom.get_ref(),
+            result_num: index,
+        }
+    }
+
+    /// Get the amount of search results.
+    pub fn num_results(&self) -> u32 {
+        let dom = self.dom.get_ref();
+        (0..)
+            .find(|result_num| {
+                dom.get_element_by_id(format!(SEARCH_COURSE_ID_TAG!(), result_num).as_str())
+                    .is_none()
+            })
+            .unwrap_or(0)
+    }
+
+    /// Iterator over search results, in the order returned by the host.
+    pub fn result_iter(&self) -> impl Iterator<Item = SearchResult<'_>> + '_ {
+        (0..self.num_results()).map(|result_num| self.result_from_index(result_num))
+    }
+}
+
+/// Parser for a raw course search result.
+#[derive(Debug, Clone, Copy)]
+pub struct SearchResult<'a> {
+    dom: &'a VDom<'a>,
+    result_num: u32,
+}
+
+impl<'a> SearchResult<'a> {
+    /// Return a model of the search result with all fields evaluated.
+    pub fn model(&self) -> Result<SearchResultModel, ParseError> {
+        self.try_into()
+    }
+
+    /// Get the course matching the search result.
+    ///
+    /// The course is always [`Course::Raw`](Course::Raw), as the search may return courses
+    /// that aren't in the catalog.
+    pub fn course(&self) -> Result<Course, ParseError> {
+        self.course_id().map(|id| Course::Raw(id.to_owned()))
+    }
+
+    /// Get the internal id of the course.
+    ///
+    /// For instance, `004229`.
+    pub fn course_id(&self) -> Result<&'a str, ParseError> {
+        get_text_from_id_without_sub_nodes(
+            self.dom,
+            &format!(SEARCH_COURSE_ID_TAG!(), self.result_num),
+        )
+        .map(|id| id.trim())
+    }
+
+    /// Get the subject of the course.
+    ///
+    /// For instance, if the result says `CSE 115 - Introduction to Computer Science I`, this
+    /// function will return `CSE`.
+    pub fn subject(&self) -> Result<&'a str, ParseError> {
+        self.descr().map(|descr| descr.0)
+    }
+
+    /// Get the catalog number of the course.
+    ///
+    /// For instance, if the result says `CSE 115 - Introduction to Computer Science I`, this
+    /// function will return `115`.
+    pub fn catalog_number(&self) -> Result<&'a str, ParseError> {
+        self.descr().map(|descr| descr.1)
+    }
+
+    /// Get the title of the course.
+    ///
+    /// For instance, if the result says `CSE 115 - Introduction to Computer Science I`, this
+    /// function will return `Introduction to Computer Science I`.
+    pub fn title(&self) -> Result<&'a str, ParseError> {
+        self.descr().map(|descr| descr.2)
+    }
+
+    /// Get various bits of information for this result in the form,
+    /// `(subject, catalog_number, title)`.
+    fn descr(&self) -> Result<(&'a str, &'a str, &'a str), ParseError> {
+        let descr = get_text_from_id_without_sub_nodes(
+            self.dom,
+            &format!(SEARCH_DESCR_TAG!(), self.result_num),
+        )?;
+
+        let re = SEARCH_DESCR_FORMAT
+            .captures(descr)
+            .ok_or(ParseError::UnknownElementFormat)?;
+        Ok((
+            re.get(1).ok_or(ParseError::UnknownElementFormat)?.as_str(),
+            re.get(2).ok_or(ParseError::UnknownElementFormat)?.as_str(),
+            re.get(3).ok_or(ParseError::UnknownElementFormat)?.as_str(),
+        ))
+    }
+}
+
 /// Day of week.
 #[derive(Debug, Clone, Copy)]
 #[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
@@ -617,6 +1124,82 @@ impl Display for DayOfWeek {
     }
 }
 
+/// Sequence numbers embedded in the ids of class tags.
+///
+/// Ids of some class tags contain a sequence number that depends on the index of the class in
+/// its group (e.g. `294`, `295`, `296` for the first three classes). Rather than hard-coding
+/// them, they are discovered from the DOM, which also determines the amount of classes in each
+/// group.
+#[derive(Debug, Default)]
+struct TagIndex {
+    // (group_num, class_num) -> sequence number
+    class_id: HashMap<(u32, u32), u32>,
+    datetime: HashMap<(u32, u32), u32>,
+    instructor: HashMap<(u32, u32), u32>,
+    // group_num -> amount of classes
+    classes: HashMap<u32, u32>,
+}
+
+impl TagIndex {
+    /// Construct a new [`TagIndex`](TagIndex) by scanning every tag id in the DOM.
+    fn new(dom: &VDom) -> Self {
+        let mut index = TagIndex::default();
+        for id in dom
+            .nodes()
+            .iter()
+            .filter_map(|node| node.as_tag()?.attributes().id())
+        {
+            let id = id.as_utf8_str();
+            // Cheap check to skip the vast majority of tags
+            if !id.starts_with("SSR_CLSRCH_F_WK_SSR_") {
+                continue;
+            }
+
+            if let Some((group_num, class_num, seq)) = Self::captures(&CLASS_ID_TAG_FORMAT, &id) {
+                index.class_id.insert((group_num, class_num), seq);
+                let classes = index.classes.entry(group_num).or_default();
+                *classes = (*classes).max(class_num + 1);
+            } else if let Some((group_num, class_num, seq)) =
+                Self::captures(&DATETIME_TAG_FORMAT, &id)
+            {
+                index.datetime.insert((group_num, class_num), seq);
+            } else if let Some((group_num, class_num, seq)) =
+                Self::captures(&INSTRUCTOR_TAG_FORMAT, &id)
+            {
+                index.instructor.insert((group_num, class_num), seq);
+            }
+        }
+
+        index
+    }
+
+    /// Get the amount of classes in the specified group.
+    fn num_classes(&self, group_num: u32) -> u32 {
+        self.classes.get(&group_num).copied().unwrap_or(0)
+    }
+
+    fn class_id_seq(&self, group_num: u32, class_num: u32) -> Option<u32> {
+        self.class_id.get(&(group_num, class_num)).copied()
+    }
+
+    fn datetime_seq(&self, group_num: u32, class_num: u32) -> Option<u32> {
+        self.datetime.get(&(group_num, class_num)).copied()
+    }
+
+    fn instructor_seq(&self, group_num: u32, class_num: u32) -> Option<u32> {
+        self.instructor.get(&(group_num, class_num)).copied()
+    }
+
+    /// Extract `(group_num, class_num, seq)` from a tag id, where `class_num` is 0-indexed.
+    fn captures(re: &Regex, id: &str) -> Option<(u32, u32, u32)> {
+        let captures = re.captures(id)?;
+        let class_num: u32 = captures.get(1)?.as_str().parse().ok()?;
+        let seq = captures.get(2)?.as_str().parse().ok()?;
+        let group_num = captures.get(3)?.as_str().parse().ok()?;
+        Some((group_num, class_num.checked_sub(1)?, seq))
+    }
+}
+
 // TODO: document
 fn get_text_from_id_without_sub_nodes<'a>(dom: &'a VDom, id: &str) -> Result<&'a str, ParseError> {
     match get_node_from_id(dom, id)?.inner_text(dom.parser()) {
@@ -628,6 +1211,54 @@ fn get_text_from_id_without_sub_nodes<'a>(dom: &'a VDom, id: &str) -> Result<&'a
     }
 }
 
+/// Parse seats in the form of the format, such as `Open Seats 5 of 30`, into `(count, total)`.
+///
+/// Returns `None` if the seats aren't in the form of the format.
+fn parse_seats(format: &Regex, seats: &str) -> Result<Option<(u32, u32)>, ParseError> {
+    let Some(captures) = format.captures(seats) else {
+        return Ok(None);
+    };
+
+    let parse = |index| {
+        captures
+            .get(index)
+            .ok_or(ParseError::UnknownElementFormat)?
+            .as_str()
+            .parse()
+            .map_err(|_| ParseError::UnknownElementFormat)
+    };
+    Ok(Some((parse(1)?, parse(2)?)))
+}
+
+/// Get the text of the element with the id, including that of its sub-nodes, with whitespace
+/// collapsed.
+fn get_text_from_id(dom: &VDom, id: &str) -> Result<String, ParseError> {
+    let text = get_node_from_id(dom, id)?.inner_text(dom.parser());
+    Ok(text.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+/// Get the text of each line of the element with the id, split by sub-nodes such as `<br />`.
+fn get_lines_from_id(dom: &VDom, id: &str) -> Result<Vec<String>, ParseError> {
+    let parser = dom.parser();
+    Ok(get_node_from_id(dom, id)?
+        .children()
+        .map(|children| {
+            children
+                .top()
+                .iter()
+                .filter_map(|handle| handle.get(parser))
+                .map(|node| {
+                    node.inner_text(parser)
+                        .split_whitespace()
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                })
+                .filter(|line| !line.is_empty())
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
 // TODO: ^
 fn get_node_from_id<'a>(dom: &'a VDom, id: &str) -> Result<&'a Node<'a>, ParseError> {
     Ok(dom
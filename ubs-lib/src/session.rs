@@ -1,35 +1,173 @@
 //! Low-level access to the host connection.
 
-use std::sync::Arc;
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, RwLock,
+    },
+    time::Duration,
+};
 
-use cookie::Cookie;
-use futures::{stream, TryFutureExt, TryStream, TryStreamExt};
+use chrono::{DateTime, Utc};
+use cookie::{time::OffsetDateTime, Cookie, CookieJar};
+use futures::{stream, TryStream};
 use hyper::{
     body::{self, Bytes},
-    client::connect::Connect,
-    header, Body, Client, HeaderMap, Request, Response,
+    header, Body, HeaderMap, Method, Request, Response, StatusCode, Uri,
 };
 use thiserror::Error;
+use tokio::{
+    sync::{self as async_sync, OwnedSemaphorePermit, Semaphore},
+    time::Instant,
+};
 
 use crate::{
     ids::{Course, Semester},
+    parser::ClassSchedule,
+    transport::Transport,
     Career,
 };
 
 const USER_AGENT: &str = "ubs";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+const BASE_URL: &str = "https://www.pub.hub.buffalo.edu";
+
+// All URLs are relative to the base URL, specified as the first parameter.
 
 // TODO: remove excess queries from url
-const FAKE1_URL: &str = "https://www.pub.hub.buffalo.edu/psc/csprdpub_1/EMPLOYEE/SA/c/SSR_STUDENT_FL.SSR_CLSRCH_MAIN_FL.GBL?Page=SSR_CLSRCH_MAIN_FL&pslnkid=CS_S201605302223124733554248&ICAJAXTrf=true&ICAJAX=1&ICMDTarget=start&ICPanelControlStyle=%20pst_side1-fixed%20pst_panel-mode%20";
-macro_rules! FAKE2_URL {
-    () => { "https://www.pub.hub.buffalo.edu/psc/csprdpub_1/EMPLOYEE/SA/c/SSR_STUDENT_FL.SSR_CLSRCH_ES_FL.GBL?SEARCH_GROUP=SSR_CLASS_SEARCH_LFF&SEARCH_TEXT=placeholder&ES_INST=UBFLO&ES_STRM={}" };
+macro_rules! FAKE1_URL {
+    () => { "{}/psc/csprdpub_1/EMPLOYEE/SA/c/SSR_STUDENT_FL.SSR_CLSRCH_MAIN_FL.GBL?Page=SSR_CLSRCH_MAIN_FL&pslnkid=CS_S201605302223124733554248&ICAJAXTrf=true&ICAJAX=1&ICMDTarget=start&ICPanelControlStyle=%20pst_side1-fixed%20pst_panel-mode%20" };
+}
+// Lists every semester offered in the term dropdown. Not yet verified against the host, which may
+// require the same query as `FAKE1_URL`.
+macro_rules! SEMESTERS_URL {
+    () => {
+        "{}/psc/csprdpub_1/EMPLOYEE/SA/c/SSR_STUDENT_FL.SSR_CLSRCH_MAIN_FL.GBL"
+    };
 }
+// Second is the percent-encoded search text
+// Third is the semester id
+macro_rules! SEARCH_URL {
+    () => { "{}/psc/csprdpub_1/EMPLOYEE/SA/c/SSR_STUDENT_FL.SSR_CLSRCH_ES_FL.GBL?SEARCH_GROUP=SSR_CLASS_SEARCH_LFF&SEARCH_TEXT={}&ES_INST=UBFLO&ES_STRM={}" };
+}
+// Searched when initializing the session, the results are ignored.
+const FAKE_SEARCH_TEXT: &str = "placeholder";
 macro_rules! PAGE1_URL {
-    () => { "https://www.pub.hub.buffalo.edu/psc/csprdpub_3/EMPLOYEE/SA/c/SSR_STUDENT_FL.SSR_CRSE_INFO_FL.GBL?CRSE_OFFER_NBR=1&INSTITUTION=UBFLO&CRSE_ID={}&STRM={}&ACAD_CAREER={}" };
+    () => { "{}/psc/csprdpub_3/EMPLOYEE/SA/c/SSR_STUDENT_FL.SSR_CRSE_INFO_FL.GBL?CRSE_OFFER_NBR=1&INSTITUTION=UBFLO&CRSE_ID={}&STRM={}&ACAD_CAREER={}" };
+}
+// Subsequent pages are `POST` requests to the same form, differing only by `ICStateNum` and
+// `ICAction`.
+macro_rules! PAGEN_URL {
+    () => {
+        "{}/psc/csprdpub_3/EMPLOYEE/SA/c/SSR_STUDENT_FL.SSR_CRSE_INFO_FL.GBL"
+    };
 }
+// First is the `ICStateNum`
+// Second is the `ICAction`
+// Third is the `ICSID` returned by the first page
+macro_rules! PAGEN_BODY {
+    () => { "ICAJAX=1&ICNAVTYPEDROPDOWN=0&ICType=Panel&ICElementNum=0&ICStateNum={}&ICAction={}&ICModelCancel=0&ICXPos=0&ICYPos=0&ResponsetoDiffFrame=-1&TargetFrameName=None&FacetPath=None&ICFocus=&ICSaveWarningFilter=0&ICChanged=-1&ICSkipPending=0&ICAutoSave=0&ICResubmit=0&ICSID={}&ICAGTarget=true&ICActionPrompt=false&ICTypeAheadID=&ICBcDomData=UnknownValue&ICPanelName=&ICFind=&ICAddCount=&ICAppClsData=" };
+}
+const PHONY_ACTION: &str = "%23ICUpdate";
+const NEXT_PAGE_ACTION: &str = "SSR_CLSRCH_F_WK_SSR_SHOW_MORE_PB";
 
-const TOKEN1_URL: &str ="https://www.pub.hub.buffalo.edu/psc/csprdpub/EMPLOYEE/SA/c/NUI_FRAMEWORK.PT_LANDINGPAGE.GBL?tab=DEFAULT";
-const TOKEN2_URL: &str ="https://www.pub.hub.buffalo.edu/psc/csprdpub/EMPLOYEE/SA/c/NUI_FRAMEWORK.PT_LANDINGPAGE.GBL?tab=DEFAULT&";
+// Redirects are followed from this URL until the token cookie is found.
+macro_rules! TOKEN_URL {
+    () => {
+        "{}/psc/csprdpub/EMPLOYEE/SA/c/NUI_FRAMEWORK.PT_LANDINGPAGE.GBL?tab=DEFAULT"
+    };
+}
 const TOKEN_COOKIE_NAME: &str = "psprd-8083-PORTAL-PSJSESSIONID";
+const MAX_REDIRECTS: u32 = 10;
+// Returned by the host in the redirect to the sign-in page once the session expires. Note that
+// every page also references it in its timeout script.
+const EXPIRED_LOCATION_MARKER: &str = "cmd=expire";
+// Returned by the host in place of the requested page once the session expires.
+const EXPIRED_PAGE_MARKER: &str = "Your session has expired";
+
+/// Set of endpoints used to communicate with the host.
+///
+/// By default, all endpoints point to the University at Buffalo's live PeopleSoft instance.
+#[derive(Debug, Clone)]
+pub struct Endpoints {
+    base_url: Arc<str>,
+}
+
+impl Endpoints {
+    /// Construct a new [`Endpoints`](Endpoints) relative to the specified base URL.
+    ///
+    /// For instance, `http://127.0.0.1:8080` for a server running locally.
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            base_url: Arc::from(base_url.trim_end_matches('/')),
+        }
+    }
+
+    /// Get the base URL of the host.
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    fn fake1(&self) -> String {
+        format!(FAKE1_URL!(), self.base_url)
+    }
+
+    fn fake2(&self, semester: &Semester) -> String {
+        self.search(FAKE_SEARCH_TEXT, semester)
+    }
+
+    fn semesters(&self) -> String {
+        format!(SEMESTERS_URL!(), self.base_url)
+    }
+
+    fn search(&self, text: &str, semester: &Semester) -> String {
+        format!(
+            SEARCH_URL!(),
+            self.base_url,
+            percent_encode(text),
+            semester.id()
+        )
+    }
+
+    fn page1(&self, query: &Query) -> String {
+        format!(
+            PAGE1_URL!(),
+            self.base_url,
+            query.course.id(),
+            query.semester.id(),
+            query.career.id()
+        )
+    }
+
+    fn pagen(&self) -> String {
+        format!(PAGEN_URL!(), self.base_url)
+    }
+
+    fn token(&self) -> String {
+        format!(TOKEN_URL!(), self.base_url)
+    }
+}
+
+impl Default for Endpoints {
+    fn default() -> Self {
+        Self::new(BASE_URL)
+    }
+}
+
+/// Page returned by the host, along with the time it was fetched.
+#[derive(Debug, Clone)]
+pub struct Page {
+    /// Body of the page.
+    pub bytes: Bytes,
+    /// Time the response was received.
+    pub fetched_at: DateTime<Utc>,
+}
+
+impl From<Page> for Vec<u8> {
+    fn from(page: Page) -> Self {
+        page.bytes.into()
+    }
+}
 
 /// Information about the course query.
 #[derive(Debug, Clone)]
@@ -48,204 +186,899 @@ impl Query {
             career,
         }
     }
+
+    /// Course being queried.
+    pub fn course(&self) -> &Course {
+        &self.course
+    }
+
+    /// Semester being queried.
+    pub fn semester(&self) -> &Semester {
+        &self.semester
+    }
+
+    /// Career of the course being queried.
+    pub fn career(&self) -> &Career {
+        &self.career
+    }
 }
 
 #[derive(Debug)]
 struct ScheduleIterState<T> {
     page_num: u32,
+    pagination: Pagination,
     query: Query,
-    client: Client<T, Body>,
-    token: Token,
+    session: Session<T>,
 }
 
-/// Manages the session to the host server.
+/// Progress of a query, updated as each page is requested.
+#[derive(Debug, Default)]
+struct Pagination {
+    // Unknown until the first page is parsed.
+    total_pages: Option<u32>,
+    page_state: Option<PageState>,
+    // Generation of the form state on the host when the first page was received, or `None` if
+    // another query may have replaced it.
+    generation: Option<u64>,
+}
+
+/// State of the PeopleSoft form required to request subsequent pages.
+#[derive(Debug, Clone)]
+struct PageState {
+    // Unique identifier for the form, returned by the first page.
+    sid: String,
+    // Incremented for every POST request sent to the form.
+    state_num: u32,
+}
+
+/// Policy for retrying failed requests with exponential backoff.
+///
+/// Requests are retried if they time out, fail to connect, the connection is closed before a
+/// response is received, or the response has one of the retryable status codes. Requests to the
+/// form (i.e. `POST` requests to subsequent pages) advance its state on the host, thus they're
+/// only retried if they fail to connect or the response has one of the retryable status codes.
+///
+/// If the last attempt still responds with an error status, the request fails with
+/// [`SessionError::UnexpectedStatus`](SessionError::UnexpectedStatus).
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Maximum amount of attempts for each request, including the first.
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled for each retry after.
+    pub base_delay: Duration,
+    /// Maximum delay between retries.
+    pub max_delay: Duration,
+    /// Randomize each delay between half and the full delay, so that concurrent requests
+    /// don't retry in lockstep.
+    pub jitter: bool,
+    /// Status codes of responses that are retried.
+    pub retry_statuses: Vec<StatusCode>,
+}
+
+impl RetryPolicy {
+    /// Construct a [`RetryPolicy`](RetryPolicy) that never retries.
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Default::default()
+        }
+    }
+
+    /// Get the delay before the specified retry, starting from 1.
+    fn delay(&self, retry: u32) -> Duration {
+        let delay = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(retry - 1))
+            .min(self.max_delay);
+        match self.jitter {
+            true => delay.mul_f64(0.5 + fastrand::f64() / 2.0),
+            false => delay,
+        }
+    }
+
+    /// Whether the result of a request should be retried.
+    ///
+    /// Stateful requests may have been processed by the host without a response being received,
+    /// in which case sending them again would desync the form state.
+    fn should_retry(&self, result: &Result<Response<Bytes>, SessionError>, stateful: bool) -> bool {
+        match result {
+            Ok(response) => self.retry_statuses.contains(&response.status()),
+            Err(SessionError::Timeout) => !stateful,
+            Err(SessionError::HttpRequestFailed(err)) => {
+                err.is_connect() || (!stateful && (err.is_closed() || err.is_incomplete_message()))
+            }
+            Err(_) => false,
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(10),
+            jitter: true,
+            retry_statuses: vec![
+                StatusCode::TOO_MANY_REQUESTS,
+                StatusCode::INTERNAL_SERVER_ERROR,
+                StatusCode::BAD_GATEWAY,
+                StatusCode::SERVICE_UNAVAILABLE,
+                StatusCode::GATEWAY_TIMEOUT,
+            ],
+        }
+    }
+}
+
+/// Limits on the rate of requests sent to the host.
+#[derive(Debug, Clone)]
+pub struct RateLimit {
+    /// Maximum amount of requests started per second.
+    pub requests_per_second: f64,
+    /// Maximum amount of requests awaiting a response at once.
+    pub max_concurrent: usize,
+}
+
+impl Default for RateLimit {
+    fn default() -> Self {
+        Self {
+            requests_per_second: 4.0,
+            max_concurrent: 4,
+        }
+    }
+}
+
+/// Enforces a [`RateLimit`](RateLimit) across every clone of a session.
 #[derive(Debug)]
+struct RateLimiter {
+    interval: Duration,
+    permits: Arc<Semaphore>,
+    // Earliest time the next request may be started.
+    next: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    fn new(rate_limit: &RateLimit) -> Self {
+        let interval = match rate_limit.requests_per_second {
+            rps if rps.is_finite() && rps > 0.0 => Duration::from_secs_f64(1.0 / rps),
+            _ => Duration::ZERO,
+        };
+        Self {
+            interval,
+            permits: Arc::new(Semaphore::new(
+                rate_limit.max_concurrent.clamp(1, Semaphore::MAX_PERMITS),
+            )),
+            next: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Wait until a request may be sent, returning a permit to hold until it completes.
+    async fn acquire(&self) -> OwnedSemaphorePermit {
+        let permit = self
+            .permits
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("semaphore is never closed");
+
+        let start = {
+            let mut next = self.next.lock().unwrap();
+            let start = (*next).max(Instant::now());
+            *next = start + self.interval;
+            start
+        };
+        tokio::time::sleep_until(start).await;
+
+        permit
+    }
+}
+
+/// Manages the session to the host server.
+///
+/// Cloning a session is cheap, and all clones share the same cookies.
+#[derive(Debug, Clone)]
 pub struct Session<T> {
-    client: Client<T, Body>,
-    token: Token,
+    transport: T,
+    cookies: Arc<RwLock<Cookies>>,
+    endpoints: Endpoints,
+    retry_policy: Arc<RetryPolicy>,
+    request_timeout: Option<Duration>,
+    total_timeout: Option<Duration>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    form: Arc<FormState>,
+}
+
+/// Tracks the form state kept by the host, of which there is only one per session.
+///
+/// Requesting the first page of a query replaces the form state, thus any amount of first pages
+/// may be requested concurrently, while the pages after require exclusive access.
+#[derive(Debug, Default)]
+struct FormState {
+    lock: async_sync::RwLock<()>,
+    // Incremented before and after every request for a first page.
+    generation: AtomicU64,
 }
 
 impl<T> Session<T> {
     /// Construct a new [`Session`](Session).
-    pub fn new(client: Client<T, Body>, token: Token) -> Self {
-        Self { client, token }
+    ///
+    /// By default, requests are retried with the default [`RetryPolicy`](RetryPolicy), each
+    /// attempt times out after 30 seconds, and requests are limited by the default
+    /// [`RateLimit`](RateLimit).
+    pub fn new(transport: T, token: Token) -> Self {
+        Self {
+            transport,
+            cookies: Arc::new(RwLock::new(token.cookies)),
+            endpoints: Endpoints::default(),
+            retry_policy: Arc::new(RetryPolicy::default()),
+            request_timeout: Some(REQUEST_TIMEOUT),
+            total_timeout: None,
+            rate_limiter: Some(Arc::new(RateLimiter::new(&RateLimit::default()))),
+            form: Arc::new(FormState::default()),
+        }
+    }
+
+    /// Use the specified [`Endpoints`](Endpoints) rather than the defaults.
+    ///
+    /// Note that the [`Token`](Token) must be acquired from the same endpoints via
+    /// [`Token::with_endpoints`](Token::with_endpoints).
+    pub fn with_endpoints(mut self, endpoints: Endpoints) -> Self {
+        self.endpoints = endpoints;
+        self
+    }
+
+    /// Use the specified [`RetryPolicy`](RetryPolicy) rather than the default.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Arc::new(retry_policy);
+        self
+    }
+
+    /// Set the maximum time for each attempt of a request to receive a response, or `None`
+    /// to wait indefinitely.
+    pub fn with_request_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.request_timeout = timeout;
+        self
+    }
+
+    /// Set the maximum time for a request to receive a response, including every retry, or
+    /// `None` to wait indefinitely.
+    pub fn with_total_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.total_timeout = timeout;
+        self
+    }
+
+    /// Limit requests to the specified [`RateLimit`](RateLimit), or `None` to send requests
+    /// without limit.
+    ///
+    /// The limit is shared between this session, its clones, its forks, and every stream they
+    /// produce. It also applies to the requests acquiring a [`Token`](Token) and initializing
+    /// the session.
+    pub fn with_rate_limit(mut self, rate_limit: Option<RateLimit>) -> Self {
+        self.rate_limiter = rate_limit.map(|rate_limit| Arc::new(RateLimiter::new(&rate_limit)));
+        self
     }
 }
 
 impl<T> Session<T>
 where
-    T: Connect + Clone + Send + Sync + 'static,
+    T: Transport,
 {
     /// Initializes the session.
     ///
-    /// This only needs to be called once before the schedule is iterated.
-    // TODO: I believe this only needs to be called once. When the token expires, a new token is needed.
-    // I also believe the semester can be any semester, as long as it is valid. So in the future it can be
+    /// This only needs to be called once before the schedule is iterated. If the session
+    /// expires, it is automatically refreshed and initialized again.
+    // I believe the semester can be any semester, as long as it is valid. So in the future it can be
     // replaced if the latest semesters are auto found
     pub async fn initialize(&self, semester: &Semester) -> Result<(), SessionError> {
-        self.client
-            .request(
-                Request::builder()
-                    .uri(FAKE1_URL)
-                    .header(header::COOKIE, self.token.as_str())
-                    .body(Body::empty())?,
-            )
+        self.send(Method::GET, &self.endpoints.fake1(), None)
             .await?;
-        self.client
-            .request(
-                Request::builder()
-                    .uri(format!(FAKE2_URL!(), semester.id()))
-                    .header(header::COOKIE, self.token.as_str())
-                    .body(Body::empty())?,
-            )
+        self.send(Method::GET, &self.endpoints.fake2(semester), None)
             .await?;
 
         Ok(())
     }
 
+    /// Acquire a new [`Token`](Token) and initialize the session again.
+    ///
+    /// This is called automatically when the session expires while iterating schedules.
+    pub async fn refresh(&self, semester: &Semester) -> Result<(), SessionError> {
+        self.renew_token().await?;
+        self.initialize(semester).await
+    }
+
+    /// Construct a new session with its own cookies and form state, initialized for the
+    /// specified semester.
+    ///
+    /// Unlike a clone, pages requested from the forked session don't replace the form state of
+    /// this session, thus queries spanning multiple pages can be iterated concurrently without
+    /// requesting prior pages again. The transport, endpoints, retry policy, timeouts and rate
+    /// limit are shared.
+    pub async fn fork(&self, semester: &Semester) -> Result<Self, SessionError> {
+        let token = self.acquire_token().await?;
+        let session = Self {
+            cookies: Arc::new(RwLock::new(token.cookies)),
+            form: Arc::new(FormState::default()),
+            ..self.clone()
+        };
+        session.initialize(semester).await?;
+
+        Ok(session)
+    }
+
+    /// Get the page listing every semester currently offered by the host.
+    ///
+    /// The returned bytes can be parsed with [`SemesterList`](crate::parser::SemesterList). Note
+    /// that the session doesn't need to be initialized beforehand.
+    pub async fn semesters(&self) -> Result<Bytes, SessionError> {
+        let uri = self.endpoints.semesters();
+        if let Some(page) = self.send_page(Method::GET, &uri, None).await? {
+            return Ok(page.bytes);
+        }
+
+        self.renew_token().await?;
+        self.send_page(Method::GET, &uri, None)
+            .await?
+            .map(|page| page.bytes)
+            .ok_or(SessionError::SessionExpired)
+    }
+
+    /// Search for courses offered in the specified semester matching the text.
+    ///
+    /// The text is matched by the host against course subjects, catalog numbers, titles, and
+    /// descriptions, for instance, `machine learning` or `CSE 4`. The returned bytes can be
+    /// parsed with [`SearchResults`](crate::parser::SearchResults).
+    pub async fn search(&self, text: &str, semester: &Semester) -> Result<Bytes, SessionError> {
+        let uri = self.endpoints.search(text, semester);
+        if let Some(page) = self.send_page(Method::GET, &uri, None).await? {
+            return Ok(page.bytes);
+        }
+
+        self.refresh(semester).await?;
+        self.send_page(Method::GET, &uri, None)
+            .await?
+            .map(|page| page.bytes)
+            .ok_or(SessionError::SessionExpired)
+    }
+
     /// Iterate over pages of schedules with the specified [`Query`](Query).
-    pub fn schedule_iter(&self, query: Query) -> impl TryStream<Ok = Bytes, Error = SessionError> {
-        stream::unfold(
+    ///
+    /// The iterator ends once the last page, as reported by the first page, has been returned.
+    /// Each page can be parsed with [`ClassSchedule::from_page`](ClassSchedule::from_page).
+    pub fn schedule_iter(&self, query: Query) -> impl TryStream<Ok = Page, Error = SessionError> {
+        stream::try_unfold(
             ScheduleIterState {
                 page_num: 1,
+                pagination: Pagination::default(),
                 query,
-                // both Arc, so it's cheap
-                client: self.client.clone(),
-                token: self.token.clone(),
+                // every field is cheap to clone
+                session: self.clone(),
             },
             |mut state| {
                 Box::pin(async move {
-                    Self::get_page(&state.client, &state.token, &state.query, state.page_num)
-                        .await
-                        .transpose()
-                        .map(|response| {
-                            state.page_num += 1;
-                            (response, state)
-                        })
+                    if let Some(total_pages) = state.pagination.total_pages {
+                        if state.page_num > total_pages {
+                            return Ok(None);
+                        }
+                    }
+
+                    let page = state
+                        .session
+                        .get_page(&state.query, state.page_num, &mut state.pagination)
+                        .await?;
+
+                    state.page_num += 1;
+                    Ok(Some((page, state)))
                 })
             },
         )
-        .and_then(|response| Box::pin(body::to_bytes(response.into_body()).err_into()))
     }
 
-    // TODO: you MUST go page-by-page, otherwise it won't return the correct result?
+    /// Acquire a new [`Token`](Token), replacing the session cookies.
+    async fn renew_token(&self) -> Result<(), SessionError> {
+        let token = self.acquire_token().await?;
+        *self.cookies.write().unwrap() = token.cookies;
+
+        Ok(())
+    }
+
+    /// Acquire a new [`Token`](Token) by following the redirect chain from the landing page.
+    ///
+    /// Each hop is sent with its own cookie jar, though it is otherwise retried, timed out and
+    /// rate limited the same as every other request of the session.
+    async fn acquire_token(&self) -> Result<Token, SessionError> {
+        // Cookies set along the redirect chain (e.g. load balancer affinity) must be sent with
+        // every subsequent request.
+        let session = Self {
+            cookies: Arc::new(RwLock::new(Cookies::default())),
+            form: Arc::new(FormState::default()),
+            ..self.clone()
+        };
+
+        let mut uri: Uri = self.endpoints.token().parse()?;
+        for _ in 0..=MAX_REDIRECTS {
+            let response = session.send(Method::GET, &uri.to_string(), None).await?;
+            let cookies = session.cookies.read().unwrap();
+            if cookies.get(TOKEN_COOKIE_NAME).is_some() {
+                return Ok(Token {
+                    cookies: cookies.clone(),
+                });
+            }
+
+            uri = match response
+                .headers()
+                .get(header::LOCATION)
+                .and_then(|location| location.to_str().ok())
+            {
+                Some(location) => resolve_location(&uri, location)?,
+                None => return Err(SessionError::TokenCookieNotFound),
+            };
+        }
+
+        Err(SessionError::TooManyRedirects)
+    }
+
     /// Get specific page for query.
     ///
-    /// Note that this must be called incrementally, page-by-page.
+    /// If the session expired, it is refreshed and the page is requested again. Because the
+    /// form state is tied to the expired session, every page prior is requested again as well.
+    /// The same applies if the form state was replaced by another query on the same session,
+    /// which [`Session::fork`](Session::fork) avoids.
     async fn get_page(
-        client: &Client<T, Body>,
-        token: &Token,
+        &self,
         query: &Query,
         page_num: u32,
-    ) -> Result<Option<Response<Body>>, SessionError> {
-        #[allow(clippy::never_loop)] // TODO: temp
-        loop {
-            match page_num {
-                1 => {
-                    let page = client
-                        .request(
-                            Request::builder()
-                                .uri(format!(
-                                    PAGE1_URL!(),
-                                    query.course.id(),
-                                    query.semester.id(),
-                                    query.career.id()
-                                ))
-                                .header(header::USER_AGENT, USER_AGENT)
-                                .header(header::COOKIE, token.as_str())
-                                .header(header::COOKIE, "HttpOnly")
-                                .header(header::COOKIE, "Path=/")
-                                .body(Body::empty())?,
-                        )
-                        .await?;
-                    // TODO: do I need to send the fake result here (with ICState=2) for the next
-                    // pages to load?
-                    return Ok(Some(page));
+        pagination: &mut Pagination,
+    ) -> Result<Page, SessionError> {
+        let (_shared, _exclusive) = match page_num {
+            1 => (Some(self.form.lock.read().await), None),
+            _ => (None, Some(self.form.lock.write().await)),
+        };
+
+        let replaced = pagination.generation != Some(self.form.generation.load(Ordering::SeqCst));
+        let page = match page_num > 1 && replaced {
+            true => self.walk_pages(query, page_num, pagination).await?,
+            false => self.try_get_page(query, page_num, pagination).await?,
+        };
+        if let Some(page) = page {
+            return Ok(page);
+        }
+
+        self.refresh(&query.semester).await?;
+        self.walk_pages(query, page_num, pagination)
+            .await?
+            .ok_or(SessionError::SessionExpired)
+    }
+
+    /// Request every page up to and including the specified page, or `None` if the session
+    /// expired.
+    async fn walk_pages(
+        &self,
+        query: &Query,
+        page_num: u32,
+        pagination: &mut Pagination,
+    ) -> Result<Option<Page>, SessionError> {
+        pagination.page_state = None;
+        for page_num in 1..page_num {
+            if self
+                .try_get_page(query, page_num, pagination)
+                .await?
+                .is_none()
+            {
+                return Ok(None);
+            }
+        }
+
+        self.try_get_page(query, page_num, pagination).await
+    }
+
+    /// Get specific page for query, or `None` if the session expired.
+    ///
+    /// Note that this must be called incrementally, page-by-page. The first page is a `GET`
+    /// request, while each subsequent page is a sequence of two `POST` requests to the form. The
+    /// first is a "phony" request that primes the form state and the second loads the next page.
+    async fn try_get_page(
+        &self,
+        query: &Query,
+        page_num: u32,
+        pagination: &mut Pagination,
+    ) -> Result<Option<Page>, SessionError> {
+        let page = match page_num {
+            1 => {
+                // If another first page was requested while this one was in flight, it's
+                // unknown which of the two the form state on the host belongs to.
+                let start = self.form.generation.fetch_add(1, Ordering::SeqCst) + 1;
+                let result = self
+                    .send_page(Method::GET, &self.endpoints.page1(query), None)
+                    .await;
+                let end = self.form.generation.fetch_add(1, Ordering::SeqCst) + 1;
+                pagination.generation = (end == start + 1).then_some(end);
+
+                let page = match result? {
+                    Some(page) => page,
+                    None => return Ok(None),
+                };
+
+                // If the page can't be parsed, stop iterating and let the caller handle the
+                // error when they parse the returned page.
+                let schedule = ClassSchedule::new(page.bytes.to_vec()).ok();
+                if pagination.total_pages.is_none() {
+                    pagination.total_pages = Some(
+                        schedule
+                            .as_ref()
+                            .and_then(|schedule| schedule.total_pages().ok())
+                            .unwrap_or(page_num),
+                    );
                 }
-                _ => {
-                    // The second page has an `ICState` of 3.
-                    let _page_num = page_num + 1;
-                    // TODO: Multiple things to know about >1 pages:
-                    //  1. Each page holds 50 groups max.
-                    //  2. They are all POST requests with a slightly differing body (ICState and
-                    //     ICAction).
-                    //  3. How I currently have it set up is not how it may actually work. Meaning,
-                    //     I know there is a second "phony" request, though invoking it does not
-                    //     seem to enable the next page to return the correct result. I'm either
-                    //     missing some minute detail in the request or I need to send more phony
-                    //     requests prior.
+                pagination.page_state = schedule
+                    .as_ref()
+                    .and_then(|schedule| schedule.sid().ok())
+                    .map(|sid| PageState { sid, state_num: 1 });
+
+                page
+            }
+            _ => {
+                let page_state = pagination
+                    .page_state
+                    .as_mut()
+                    .ok_or(SessionError::PageStateNotFound)?;
+
+                // The phony request has an `ICStateNum` of 2, and the second page an `ICStateNum`
+                // of 3. Every page after that increments it by 2 accordingly.
+                page_state.state_num += 1;
+                let phony = self
+                    .send_page(
+                        Method::POST,
+                        &self.endpoints.pagen(),
+                        Some(Self::page_body(page_state, PHONY_ACTION)),
+                    )
+                    .await?;
+                if phony.is_none() {
                     return Ok(None);
                 }
+
+                page_state.state_num += 1;
+                match self
+                    .send_page(
+                        Method::POST,
+                        &self.endpoints.pagen(),
+                        Some(Self::page_body(page_state, NEXT_PAGE_ACTION)),
+                    )
+                    .await?
+                {
+                    Some(page) => page,
+                    None => return Ok(None),
+                }
+            }
+        };
+
+        Ok(Some(page))
+    }
+
+    /// Build the body of a `POST` request to the form with the specified `ICAction`.
+    fn page_body(page_state: &PageState, action: &str) -> String {
+        format!(PAGEN_BODY!(), page_state.state_num, action, page_state.sid)
+    }
+
+    /// Send a request for a page and return it, or `None` if the session expired.
+    async fn send_page(
+        &self,
+        method: Method,
+        uri: &str,
+        body: Option<String>,
+    ) -> Result<Option<Page>, SessionError> {
+        let response = self.send(method, uri, body).await?;
+        let fetched_at = Utc::now();
+        let status = response.status();
+
+        // When the session expires, the host either redirects to the sign-in page or returns it
+        // in place of the requested page.
+        let redirected = response
+            .headers()
+            .get(header::LOCATION)
+            .and_then(|location| location.to_str().ok())
+            .is_some_and(|location| location.contains(EXPIRED_LOCATION_MARKER));
+        let bytes = response.into_body();
+        if redirected || is_expired_page(&bytes) {
+            return Ok(None);
+        }
+        if !status.is_success() {
+            return Err(SessionError::UnexpectedStatus(status));
+        }
+
+        Ok(Some(Page { bytes, fetched_at }))
+    }
+
+    /// Send a request to the host, retrying according to the [`RetryPolicy`](RetryPolicy).
+    ///
+    /// If a body is specified, it is sent as a url-encoded form. Responses with an error status
+    /// fail with [`SessionError::UnexpectedStatus`](SessionError::UnexpectedStatus), while
+    /// redirects are returned as is.
+    async fn send(
+        &self,
+        method: Method,
+        uri: &str,
+        body: Option<String>,
+    ) -> Result<Response<Bytes>, SessionError> {
+        let uri: Uri = uri.parse()?;
+        // The only `POST` requests are to the form
+        let stateful = method == Method::POST;
+        let attempts = async {
+            let mut attempt = 1;
+            loop {
+                let result = self
+                    .send_once(method.clone(), uri.clone(), body.clone())
+                    .await;
+                if attempt >= self.retry_policy.max_attempts
+                    || !self.retry_policy.should_retry(&result, stateful)
+                {
+                    return result;
+                }
+
+                tokio::time::sleep(self.retry_policy.delay(attempt)).await;
+                attempt += 1;
             }
+        };
+
+        let response = match self.total_timeout {
+            Some(timeout) => tokio::time::timeout(timeout, attempts)
+                .await
+                .map_err(|_| SessionError::Timeout)??,
+            None => attempts.await?,
+        };
+        let status = response.status();
+        if status.is_client_error() || status.is_server_error() {
+            return Err(SessionError::UnexpectedStatus(status));
+        }
+
+        Ok(response)
+    }
+
+    /// Send a single request to the host with the session cookies attached and read its body.
+    ///
+    /// The session cookies are then updated from the response.
+    async fn send_once(
+        &self,
+        method: Method,
+        uri: Uri,
+        body: Option<String>,
+    ) -> Result<Response<Bytes>, SessionError> {
+        let _permit = match &self.rate_limiter {
+            Some(rate_limiter) => Some(rate_limiter.acquire().await),
+            None => None,
+        };
+
+        let mut builder = Request::builder()
+            .method(method)
+            .header(header::USER_AGENT, USER_AGENT);
+        if let Some(cookies) = self.cookies.read().unwrap().header(&uri) {
+            builder = builder.header(header::COOKIE, cookies);
         }
+        let body = match body {
+            Some(body) => {
+                builder = builder.header(header::CONTENT_TYPE, "application/x-www-form-urlencoded");
+                Body::from(body)
+            }
+            None => Body::empty(),
+        };
+
+        let request = self.transport.send(builder.uri(uri).body(body)?);
+        // The body is read within the timeout, so that a response trickling in times out as
+        // well. It's also always consumed, otherwise the connection won't be reused.
+        let request = async {
+            let (parts, body) = request.await?.into_parts();
+            let bytes = body::to_bytes(body).await?;
+            Ok::<_, SessionError>(Response::from_parts(parts, bytes))
+        };
+        let response = match self.request_timeout {
+            Some(timeout) => tokio::time::timeout(timeout, request)
+                .await
+                .map_err(|_| SessionError::Timeout)??,
+            None => request.await?,
+        };
+        self.cookies.write().unwrap().update(response.headers());
+
+        Ok(response)
     }
 }
 
-/// Contains a unique identifier for the current session.
+/// Whether the page is the one returned by the host in place of the requested page once the
+/// session expires.
+///
+/// Every page has a timeout script mentioning the expiry, thus scripts are skipped.
+fn is_expired_page(bytes: &[u8]) -> bool {
+    let mut rest = bytes;
+    loop {
+        let (text, script) = match find(rest, b"<script") {
+            Some(start) => (&rest[..start], Some(&rest[start..])),
+            None => (rest, None),
+        };
+        if find(text, EXPIRED_PAGE_MARKER.as_bytes()).is_some() {
+            return true;
+        }
+
+        match script.and_then(|script| {
+            find(script, b"</script>").map(|end| &script[end + b"</script>".len()..])
+        }) {
+            Some(next) => rest = next,
+            None => return false,
+        }
+    }
+}
+
+/// Find the position of the needle in the haystack, ignoring ASCII case.
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window.eq_ignore_ascii_case(needle))
+}
+
+/// Contains the cookies identifying the current session.
 #[derive(Debug, Clone)]
-pub struct Token(Arc<str>);
+pub struct Token {
+    cookies: Cookies,
+}
 
 impl Token {
-    /// Construct a new [`Token`](Token) with the specified [`Client`](Client).
-    pub async fn new<T>(client: &Client<T, Body>) -> Result<Self, SessionError>
+    /// Construct a new [`Token`](Token) with the specified [`Transport`](Transport).
+    pub async fn new<T>(transport: &T) -> Result<Self, SessionError>
     where
-        T: Connect + Clone + Send + Sync + 'static,
+        T: Transport,
     {
-        // TODO: need to follow redirect returned by this URL, two ways to do this:
-        //  1. Make a loop and do some magic, hopefully it works.
-        //  2. Go to 1st redirect.
-        //  3. Just use reqwest.
-        let response = client
-            .request(
-                Request::builder()
-                    .uri(TOKEN1_URL)
-                    .header(header::USER_AGENT, USER_AGENT)
-                    // TODO: may or may not need the httponly and path cookies
-                    .body(Body::empty())?,
-            )
-            .await?;
-        let response = client
-            .request(
-                Request::builder()
-                    .uri(TOKEN2_URL)
-                    .header(header::USER_AGENT, USER_AGENT)
-                    .header(
-                        header::COOKIE,
-                        Token::token_cookie(response.headers())
-                            .ok_or(SessionError::TokenCookieNotFound)?
-                            .to_string(),
-                    )
-                    // TODO: may or may not need the httponly and path cookies
-                    .body(Body::empty())?,
-            )
-            .await?;
+        Token::with_endpoints(transport, &Endpoints::default()).await
+    }
 
-        Ok(Self(Arc::from(
-            Token::token_cookie(response.headers())
-                .ok_or(SessionError::TokenCookieNotFound)?
-                .to_string(),
-        )))
+    /// Construct a new [`Token`](Token) with the specified [`Transport`](Transport) and
+    /// [`Endpoints`](Endpoints).
+    ///
+    /// Requests are retried, timed out and rate limited the same as a [`Session`](Session) with
+    /// the default settings.
+    pub async fn with_endpoints<T>(
+        transport: &T,
+        endpoints: &Endpoints,
+    ) -> Result<Self, SessionError>
+    where
+        T: Transport,
+    {
+        Session::new(
+            transport.clone(),
+            Token {
+                cookies: Cookies::default(),
+            },
+        )
+        .with_endpoints(endpoints.clone())
+        .acquire_token()
+        .await
     }
+}
 
-    /// Convert the token to its string form.
-    fn as_str(&self) -> &str {
-        &self.0
+/// Jar of cookies set by the host.
+///
+/// Each request is sent with the cookies whose path matches the path of the request.
+#[derive(Debug, Clone, Default)]
+struct Cookies(CookieJar);
+
+impl Cookies {
+    /// Get the cookie with the specified name.
+    fn get(&self, name: &str) -> Option<&Cookie<'static>> {
+        self.0.get(name)
     }
 
-    /// Fetch the [`Cookie`](Cookie) object from the specified headers.
-    fn token_cookie(headers: &HeaderMap) -> Option<Cookie<'_>> {
-        headers
+    /// Update the jar from the `Set-Cookie` headers of a response.
+    ///
+    /// Cookies that are set as expired are removed from the jar.
+    fn update(&mut self, headers: &HeaderMap) {
+        let set_cookies = headers
             .get_all(header::SET_COOKIE)
             .iter()
-            // TODO: collect errors and return them if no cookie was found
             // If it can't be parsed then skip it
             .filter_map(|string| {
                 string
                     .to_str()
                     .ok()
                     .and_then(|raw_cookie| Cookie::parse(raw_cookie).ok())
-            })
-            .find(|cookie| cookie.name() == TOKEN_COOKIE_NAME)
+            });
+
+        for cookie in set_cookies {
+            let expired = cookie
+                .max_age()
+                .is_some_and(|max_age| max_age.is_zero() || max_age.is_negative())
+                || cookie
+                    .expires_datetime()
+                    .is_some_and(|expires| expires <= OffsetDateTime::now_utc());
+
+            if expired {
+                self.0.remove(Cookie::from(cookie.name().to_owned()));
+            } else {
+                self.0.add(cookie.into_owned());
+            }
+        }
+    }
+
+    /// Build the value of the `Cookie` header for a request to the specified URI.
+    fn header(&self, uri: &Uri) -> Option<String> {
+        let is_https = uri.scheme_str() == Some("https");
+        let cookies = self
+            .0
+            .iter()
+            .filter(|cookie| is_https || cookie.secure() != Some(true))
+            .filter(|cookie| path_matches(cookie.path().unwrap_or("/"), uri.path()))
+            .map(|cookie| cookie.stripped().to_string())
+            .collect::<Vec<_>>();
+
+        match cookies.is_empty() {
+            true => None,
+            false => Some(cookies.join("; ")),
+        }
+    }
+}
+
+/// Whether a cookie with the specified path should be sent with a request to the specified path.
+///
+/// See more [here](https://datatracker.ietf.org/doc/html/rfc6265#section-5.1.4).
+fn path_matches(cookie_path: &str, request_path: &str) -> bool {
+    match request_path.strip_prefix(cookie_path) {
+        Some(rest) => rest.is_empty() || cookie_path.ends_with('/') || rest.starts_with('/'),
+        None => false,
+    }
+}
+
+/// Percent-encode every byte of the text other than unreserved characters, as per RFC 3986.
+fn percent_encode(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+
+    encoded
+}
+
+/// Resolve the `Location` header of a redirect relative to the URI that was requested.
+fn resolve_location(base: &Uri, location: &str) -> Result<Uri, SessionError> {
+    let scheme = base.scheme_str().unwrap_or("https");
+    // Relative to the scheme of the base, such as `//host/path`
+    if let Some(rest) = location.strip_prefix("//") {
+        return Ok(format!("{scheme}://{rest}").parse()?);
+    }
+    if has_scheme(location) {
+        return Ok(location.parse()?);
+    }
+
+    let path_and_query = if location.starts_with('/') {
+        location.to_owned()
+    } else {
+        // Relative to the directory of the base path
+        let base_path = base.path();
+        format!(
+            "{}{}",
+            &base_path[..base_path.rfind('/').map_or(0, |index| index + 1)],
+            location
+        )
+    };
+
+    Ok(Uri::builder()
+        .scheme(scheme)
+        .authority(base.authority().map_or("", |authority| authority.as_str()))
+        .path_and_query(path_and_query)
+        .build()?)
+}
+
+/// Whether the URI reference starts with a scheme, such as `https:`.
+///
+/// See more [here](https://datatracker.ietf.org/doc/html/rfc3986#section-3.1).
+fn has_scheme(location: &str) -> bool {
+    match location.split_once(':') {
+        Some((scheme, _)) => {
+            scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        }
+        None => false,
     }
 }
 
@@ -266,4 +1099,30 @@ pub enum SessionError {
     /// Could not find or parse the token cookie.
     #[error("could not find or parse the token cookie")]
     TokenCookieNotFound,
+    /// Followed too many redirects while acquiring the token.
+    #[error("followed too many redirects while acquiring the token")]
+    TooManyRedirects,
+    /// A URI, such as the `Location` of a redirect, was invalid.
+    #[error("could not parse URI with an invalid format")]
+    MalformedUri(#[from] hyper::http::uri::InvalidUri),
+    /// Could not find the form state required to request subsequent pages.
+    #[error(
+        "could not find the form state in the first page, required to request subsequent pages"
+    )]
+    PageStateNotFound,
+    /// The request did not receive a response in time.
+    #[error("the request timed out")]
+    Timeout,
+    /// The host responded with an unexpected status, even after retrying.
+    #[error("the host responded with unexpected status `{0}`")]
+    UnexpectedStatus(StatusCode),
+    /// The session expired and could not be refreshed.
+    #[error("the session expired and could not be refreshed")]
+    SessionExpired,
+    /// Failed to read or write a fixture.
+    #[error("failed to read or write fixture")]
+    FixtureIo(#[from] std::io::Error),
+    /// Could not find a recorded fixture for the request.
+    #[error("could not find a fixture for `{method} {uri}`")]
+    FixtureNotFound { method: Method, uri: String },
 }
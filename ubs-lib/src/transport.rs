@@ -0,0 +1,276 @@
+//! Low-level transport used to send requests to the host.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use futures::{future::BoxFuture, TryFutureExt};
+use hyper::{
+    body::{self, Bytes},
+    client::connect::Connect,
+    header::{HeaderName, HeaderValue},
+    Body, Client, HeaderMap, Request, Response, StatusCode, Uri,
+};
+
+use crate::session::SessionError;
+
+const REQUEST_EXTENSION: &str = "request";
+const RESPONSE_EXTENSION: &str = "response";
+
+/// Sends HTTP requests to the host.
+///
+/// This is implemented for [`Client`](Client), though it may be implemented manually to run a
+/// [`Session`](crate::session::Session) against something other than a live network connection.
+pub trait Transport: Clone + Send + Sync + 'static {
+    /// Send the request and return its response.
+    fn send(
+        &self,
+        request: Request<Body>,
+    ) -> BoxFuture<'static, Result<Response<Body>, SessionError>>;
+}
+
+impl<C> Transport for Client<C, Body>
+where
+    C: Connect + Clone + Send + Sync + 'static,
+{
+    fn send(
+        &self,
+        request: Request<Body>,
+    ) -> BoxFuture<'static, Result<Response<Body>, SessionError>> {
+        Box::pin(self.request(request).err_into())
+    }
+}
+
+/// Transport that saves every request/response pair to a fixture directory.
+///
+/// Each pair is saved as two files, `<n>.request` and `<n>.response`, where `n` is the order
+/// in which the request was sent. The fixtures can be served back with
+/// [`ReplayTransport`](ReplayTransport).
+#[derive(Debug, Clone)]
+pub struct RecordingTransport<T> {
+    inner: T,
+    dir: Arc<Path>,
+    count: Arc<AtomicUsize>,
+}
+
+impl<T> RecordingTransport<T> {
+    /// Construct a new [`RecordingTransport`](RecordingTransport) wrapping the specified
+    /// [`Transport`](Transport).
+    ///
+    /// The fixture directory is created if it doesn't already exist.
+    pub fn new(inner: T, dir: impl Into<PathBuf>) -> io::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+
+        Ok(Self {
+            inner,
+            dir: Arc::from(dir),
+            count: Arc::new(AtomicUsize::new(0)),
+        })
+    }
+}
+
+impl<T> Transport for RecordingTransport<T>
+where
+    T: Transport,
+{
+    fn send(
+        &self,
+        request: Request<Body>,
+    ) -> BoxFuture<'static, Result<Response<Body>, SessionError>> {
+        let this = self.clone();
+        Box::pin(async move {
+            // Bodies can only be read once, so they must be buffered and rebuilt
+            let (parts, request_body) = request.into_parts();
+            let request_body = body::to_bytes(request_body).await?;
+            let mut request_fixture =
+                format!("{} {}\n\n", parts.method, path_and_query(&parts.uri)).into_bytes();
+            request_fixture.extend_from_slice(&request_body);
+
+            let response = this
+                .inner
+                .send(Request::from_parts(parts, Body::from(request_body)))
+                .await?;
+            let (parts, response_body) = response.into_parts();
+            let response_body = body::to_bytes(response_body).await?;
+            let mut response_fixture = format!("{}\n", parts.status.as_u16());
+            for (name, value) in &parts.headers {
+                response_fixture.push_str(&format!(
+                    "{}: {}\n",
+                    name,
+                    value.to_str().unwrap_or_default()
+                ));
+            }
+            response_fixture.push('\n');
+            let mut response_fixture = response_fixture.into_bytes();
+            response_fixture.extend_from_slice(&response_body);
+
+            let index = this.count.fetch_add(1, Ordering::SeqCst);
+            fs::write(
+                this.dir.join(format!("{index:04}.{REQUEST_EXTENSION}")),
+                request_fixture,
+            )?;
+            fs::write(
+                this.dir.join(format!("{index:04}.{RESPONSE_EXTENSION}")),
+                response_fixture,
+            )?;
+
+            Ok(Response::from_parts(parts, Body::from(response_body)))
+        })
+    }
+}
+
+/// Transport that serves back fixtures saved by [`RecordingTransport`](RecordingTransport).
+///
+/// Requests are matched by their method, path, query, and body, so fixtures recorded from one
+/// host can be replayed regardless of the [`Endpoints`](crate::session::Endpoints) in use.
+/// Identical requests are answered in the order they were recorded, repeating the last response
+/// once they run out.
+#[derive(Debug, Clone)]
+pub struct ReplayTransport {
+    exchanges: Arc<Mutex<HashMap<ExchangeKey, VecDeque<RecordedResponse>>>>,
+}
+
+// (method, path and query, body)
+type ExchangeKey = (String, String, Bytes);
+
+#[derive(Debug, Clone)]
+struct RecordedResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+impl ReplayTransport {
+    /// Construct a new [`ReplayTransport`](ReplayTransport) from the specified fixture directory.
+    pub fn new(dir: impl AsRef<Path>) -> io::Result<Self> {
+        let mut requests = fs::read_dir(dir.as_ref())?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()?;
+        requests.retain(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == REQUEST_EXTENSION)
+        });
+        requests.sort();
+
+        let mut exchanges: HashMap<_, VecDeque<_>> = HashMap::new();
+        for request in requests {
+            let response = fs::read(request.with_extension(RESPONSE_EXTENSION))?;
+            exchanges
+                .entry(Self::parse_request(&fs::read(&request)?)?)
+                .or_default()
+                .push_back(Self::parse_response(&response)?);
+        }
+
+        Ok(Self {
+            exchanges: Arc::new(Mutex::new(exchanges)),
+        })
+    }
+
+    fn parse_request(fixture: &[u8]) -> io::Result<ExchangeKey> {
+        let (head, body) = split_fixture(fixture)?;
+        let (method, uri) = head.split_once(' ').ok_or_else(invalid_fixture)?;
+        Ok((
+            method.to_owned(),
+            uri.to_owned(),
+            Bytes::copy_from_slice(body),
+        ))
+    }
+
+    fn parse_response(fixture: &[u8]) -> io::Result<RecordedResponse> {
+        let (head, body) = split_fixture(fixture)?;
+        let mut lines = head.lines();
+        let status = lines
+            .next()
+            .and_then(|status| status.parse::<u16>().ok())
+            .and_then(|status| StatusCode::from_u16(status).ok())
+            .ok_or_else(invalid_fixture)?;
+
+        let mut headers = HeaderMap::new();
+        for line in lines {
+            let (name, value) = line.split_once(": ").ok_or_else(invalid_fixture)?;
+            headers.append(
+                HeaderName::from_bytes(name.as_bytes()).map_err(|_| invalid_fixture())?,
+                HeaderValue::from_str(value).map_err(|_| invalid_fixture())?,
+            );
+        }
+
+        Ok(RecordedResponse {
+            status,
+            headers,
+            body: Bytes::copy_from_slice(body),
+        })
+    }
+
+    fn find(&self, key: &ExchangeKey) -> Option<RecordedResponse> {
+        let mut exchanges = self.exchanges.lock().unwrap();
+        let responses = exchanges.get_mut(key)?;
+        match responses.len() {
+            0 => None,
+            1 => responses.front().cloned(),
+            _ => responses.pop_front(),
+        }
+    }
+}
+
+impl Transport for ReplayTransport {
+    fn send(
+        &self,
+        request: Request<Body>,
+    ) -> BoxFuture<'static, Result<Response<Body>, SessionError>> {
+        let this = self.clone();
+        Box::pin(async move {
+            let (parts, body) = request.into_parts();
+            let key = (
+                parts.method.to_string(),
+                path_and_query(&parts.uri),
+                body::to_bytes(body).await?,
+            );
+
+            let recorded = this
+                .find(&key)
+                .ok_or_else(|| SessionError::FixtureNotFound {
+                    method: parts.method.clone(),
+                    uri: parts.uri.to_string(),
+                })?;
+            let mut response = Response::builder().status(recorded.status);
+            if let Some(headers) = response.headers_mut() {
+                *headers = recorded.headers;
+            }
+
+            Ok(response.body(Body::from(recorded.body))?)
+        })
+    }
+}
+
+fn path_and_query(uri: &Uri) -> String {
+    uri.path_and_query()
+        .map(|path_and_query| path_and_query.as_str())
+        .unwrap_or("/")
+        .to_owned()
+}
+
+/// Split a fixture into its head and body, separated by an empty line.
+fn split_fixture(fixture: &[u8]) -> io::Result<(&str, &[u8])> {
+    let index = fixture
+        .windows(2)
+        .position(|window| window == b"\n\n")
+        .ok_or_else(invalid_fixture)?;
+    Ok((
+        std::str::from_utf8(&fixture[..index]).map_err(|_| invalid_fixture())?,
+        &fixture[index + 2..],
+    ))
+}
+
+fn invalid_fixture() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        "fixture is in an invalid format",
+    )
+}
@@ -0,0 +1,152 @@
+use ubs_lib::{catalog::CatalogError, Career, Catalog, Course, Credits};
+
+// Mix of courses in and out of the compiled catalog, with and without careers
+const CATALOG_CSV: &str = "004544,UGRD,CSE115LLR\r
+999001,,CSE799SEM\r
+999002,,CSE799LEC\r
+999003,,LAW505SEM\r
+999004,,ORB312LEC\r
+";
+
+#[test]
+fn lookup_by_id_and_name() -> Result<(), CatalogError> {
+    let catalog = Catalog::from_csv(CATALOG_CSV.as_bytes())?;
+    assert_eq!(catalog.entries().count(), 5);
+
+    assert_eq!(catalog.get("999001").unwrap().name, "CSE799SEM");
+    assert_eq!(catalog.get("cse 799-sem").unwrap().id, "999001");
+    // Defaults to the lecture if the component isn't specified
+    assert_eq!(catalog.get("CSE799").unwrap().id, "999002");
+    assert!(catalog.get("CSE800").is_none());
+
+    Ok(())
+}
+
+#[test]
+fn course() -> Result<(), CatalogError> {
+    let catalog = Catalog::from_csv(CATALOG_CSV.as_bytes())?;
+
+    // Courses also in the compiled catalog are returned as their variant
+    assert!(matches!(
+        catalog.course("cse115llr"),
+        Some(Course::Cse115Llr)
+    ));
+    assert!(matches!(catalog.course("004544"), Some(Course::Cse115Llr)));
+    assert!(matches!(catalog.course("CSE 799"), Some(Course::Raw(id)) if id == "999002"));
+    // Falls back to the compiled catalog
+    assert!(matches!(catalog.course("APY106"), Some(Course::Apy106Lec)));
+    assert!(catalog.course("CSE800").is_none());
+
+    Ok(())
+}
+
+#[test]
+fn career() -> Result<(), CatalogError> {
+    let catalog = Catalog::from_csv(CATALOG_CSV.as_bytes())?;
+
+    let career = |name| {
+        catalog
+            .course(name)
+            .and_then(|course| catalog.career(&course))
+    };
+    assert!(matches!(career("CSE799SEM"), Some(Career::Graduate)));
+    assert!(matches!(career("LAW505SEM"), Some(Career::Law)));
+    assert!(matches!(career("ORB312"), Some(Career::DentalMedicine)));
+    assert!(matches!(career("CSE115LLR"), Some(Career::Undergraduate)));
+    assert!(catalog.career(&Course::Raw("000000".to_owned())).is_none());
+
+    Ok(())
+}
+
+#[test]
+fn suggestions() -> Result<(), CatalogError> {
+    let catalog = Catalog::from_csv(CATALOG_CSV.as_bytes())?;
+
+    let suggestions = catalog.suggestions("CSE799SEN");
+    assert!(matches!(&suggestions[0], Course::Raw(id) if id == "999001"));
+    // Includes courses from the compiled catalog
+    let suggestions = catalog.suggestions("CSE115LEC");
+    assert!(matches!(suggestions[0], Course::Cse115Llr));
+
+    Ok(())
+}
+
+#[test]
+fn empty_catalog() {
+    let catalog = Catalog::default();
+    assert!(matches!(catalog.course("CSE 115"), Some(Course::Cse115Llr)));
+    assert!(matches!(
+        catalog.career(&Course::Cse115Llr),
+        Some(Career::Undergraduate)
+    ));
+}
+
+#[test]
+fn metadata() -> Result<(), CatalogError> {
+    let csv = "999001,,CSE799SEM,Special Topics,1-3,Thematic Pathway; Capstone,Computer Science and Engineering\r\n\
+               999002,,CSE799LEC,,,,\r\n";
+    let catalog = Catalog::from_csv(csv.as_bytes())?;
+
+    let entry = catalog.get("CSE799SEM").unwrap();
+    assert_eq!(entry.title.as_deref(), Some("Special Topics"));
+    assert_eq!(entry.credits, Some(Credits { min: 1.0, max: 3.0 }));
+    assert_eq!(entry.pathways, ["Thematic Pathway", "Capstone"]);
+    assert_eq!(
+        entry.department.as_deref(),
+        Some("Computer Science and Engineering")
+    );
+
+    let entry = catalog.get("CSE799LEC").unwrap();
+    assert_eq!(entry.title, None);
+    assert_eq!(entry.credits, None);
+    assert!(entry.pathways.is_empty());
+
+    let result = Catalog::from_csv("999001,,CSE799SEM,,four\r\n".as_bytes());
+    assert!(matches!(result, Err(CatalogError::InvalidCredits(credits)) if credits == "four"));
+
+    Ok(())
+}
+
+#[test]
+fn write_csv() -> Result<(), CatalogError> {
+    let catalog = Catalog::from_csv(CATALOG_CSV.as_bytes())?;
+    let mut csv = Vec::new();
+    catalog.write_csv(&mut csv)?;
+
+    // Inferred careers are written, otherwise entries are unchanged
+    let expected = CATALOG_CSV
+        .replace("999001,,", "999001,GRAD,")
+        .replace("999002,,", "999002,GRAD,")
+        .replace("999003,,", "999003,LAW,")
+        .replace("999004,,", "999004,SDM,");
+    assert_eq!(String::from_utf8(csv).unwrap(), expected);
+
+    Ok(())
+}
+
+#[test]
+fn invalid_id() {
+    let result = Catalog::from_csv("12,,CSE799SEM\r\n".as_bytes());
+    assert!(matches!(result, Err(CatalogError::InvalidId(id)) if id == "12"));
+}
+
+#[test]
+fn invalid_name() {
+    let result = Catalog::from_csv("999001,,CSE-799-SEM-2\r\n".as_bytes());
+    assert!(matches!(result, Err(CatalogError::InvalidName(name)) if name == "CSE-799-SEM-2"));
+}
+
+#[cfg(feature = "serde_support")]
+#[test]
+fn from_json() -> Result<(), CatalogError> {
+    let json = r#"[
+        {"id": "999001", "name": "CSE799SEM"},
+        {"id": "999004", "career": "SDM", "name": "ORB312LEC"}
+    ]"#;
+    let catalog = Catalog::from_json(json.as_bytes())?;
+
+    assert_eq!(catalog.get("CSE799").unwrap().career, "GRAD");
+    assert_eq!(catalog.get("999004").unwrap().career, "SDM");
+
+    Ok(())
+}
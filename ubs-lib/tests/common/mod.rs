@@ -0,0 +1,441 @@
+//! Utilities shared between integration tests.
+
+#![allow(dead_code)]
+
+/// Sequence numbers for the first three classes in a group, as seen on the live site.
+const CLASS_ID_SEQ: [u32; 3] = [294, 295, 296];
+const DATETIME_SEQ: [u32; 3] = [134, 135, 154];
+const INSTRUCTOR_SEQ: [u32; 3] = [86, 161, 162];
+
+/// Details of the course included in every schedule page.
+///
+/// Note that the element ids are taken from the parser rather than a recorded page, thus they
+/// only test the parser against itself.
+const COURSE_DETAILS: &str = "<span id='SSR_CRSE_INFO_V_COURSE_TITLE_LONG'>Introduction to Computer Science I</span>\
+    <span id='SSR_CRSE_INFO_V_DESCRLONG'>Introduces computer science\n through <a>programming</a>.</span>\
+    <span id='SSR_CRSE_INFO_V_SSR_UNITS_RANGE'>4 Units</span>\
+    <span id='SSR_CRSE_INFO_V_SSR_GRADE_BASIS'>Graded</span>\
+    <span id='SSR_CRSE_INFO_V_SSR_REQUISITE_LONG'>Corequisite: <a>MTH 141</a></span>";
+
+/// Generate a schedule page mimicking the HTML returned by the host.
+///
+/// Each element in `groups` is the amount of classes in that group, while `viewed` is the amount
+/// of groups included in the page (as every page includes the groups of the previous pages).
+pub fn schedule_html(groups: &[u32], viewed: usize) -> String {
+    let mut html = String::from("<html><body>");
+    html.push_str("<input type='hidden' name='ICSID' id='ICSID' value='sid123' />");
+    html.push_str("<span id='TERM_VAL_TBL_DESCR'>Spring 2024</span>");
+    html.push_str(COURSE_DETAILS);
+    html.push_str(&format!(
+        "<span id='SSR_CLSRCH_F_WK_SSR_MSG_TEXT'>1 - {} of {} options</span>",
+        viewed,
+        groups.len()
+    ));
+
+    for (group_num, &classes) in groups.iter().enumerate().take(viewed) {
+        html.push_str(&format!(
+            "<div><span id='SSR_DER_CS_GRP_SESSION_CODE$215$${group_num}'>University 15 Week Session</span>"
+        ));
+        html.push_str(&format!(
+            "<span id='SSR_CLSRCH_F_WK_SSR_MTG_DT_LONG_1$88$${group_num}'>01/22/2024 - 05/10/2024</span>"
+        ));
+
+        for class_num in 0..classes as usize {
+            let slot = class_num + 1;
+            let seq = |seqs: [u32; 3]| seqs.get(class_num).copied().unwrap_or(300 + slot as u32);
+            html.push_str(&format!(
+                "<span id='SSR_CLSRCH_F_WK_SSR_CMPNT_DESCR_{slot}${}$${group_num}'>Class Nbr {} - Section A{slot} {}</span>",
+                seq(CLASS_ID_SEQ),
+                10000 + group_num * 10 + class_num,
+                if class_num == 0 { "LEC" } else { "REC" },
+            ));
+            html.push_str(&format!(
+                "<span id='SSR_CLSRCH_F_WK_SSR_MTG_SCHED_L_{slot}${}$${group_num}'>Monday Wednesday <br />10:00AM to 10:50AM</span>",
+                seq(DATETIME_SEQ),
+            ));
+            html.push_str(&format!(
+                "<span id='SSR_CLSRCH_F_WK_SSR_MTG_LOC_LONG_{slot}${group_num}'>Nsc 215</span>"
+            ));
+            html.push_str(&format!(
+                "<span id='SSR_CLSRCH_F_WK_SSR_INSTR_LONG_{slot}${}$${group_num}'>Doe,Jane</span>",
+                seq(INSTRUCTOR_SEQ),
+            ));
+            html.push_str(&format!(
+                "<span id='SSR_CLSRCH_F_WK_SSR_DESCR50_{slot}${group_num}'>Open Seats 5 of 30</span>"
+            ));
+        }
+        html.push_str("</div>");
+    }
+
+    html.push_str("</body></html>");
+    html
+}
+
+/// Semesters offered by the mock host, in the form `(id, name)`.
+pub const SEMESTERS: [(&str, &str); 3] = [
+    ("2241", "Spring 2024"),
+    ("2246", "Summer 2024"),
+    ("2249", "Fall 2024"),
+];
+
+/// Generate a class search page with the term dropdown, mimicking the HTML returned by the host.
+///
+/// Each semester is in the form `(id, name)`.
+pub fn semesters_html(semesters: &[(&str, &str)]) -> String {
+    let mut html = String::from("<html><body><select id='CLASS_SRCH_WRK2_STRM$35$'>");
+    html.push_str("<option value=''></option>");
+    for (id, name) in semesters {
+        html.push_str(&format!("<option value='{id}'>{name}</option>"));
+    }
+
+    html.push_str("</select></body></html>");
+    html
+}
+
+/// Courses searchable on the mock host, in the form `(course_id, description)`.
+pub const SEARCH_CATALOG: [(&str, &str); 3] = [
+    ("004229", "CSE 115 - Introduction to Computer Science I"),
+    ("004230", "CSE 116 - Introduction to Computer Science II"),
+    ("004511", "CSE 474 - Introduction to Machine Learning"),
+];
+
+/// Generate a search results page mimicking the HTML returned by the host.
+///
+/// Note that the element ids are taken from the parser rather than a recorded response, thus
+/// they only test the parser against itself.
+///
+/// Each result is in the form `(course_id, description)`.
+pub fn search_html(results: &[(&str, &str)]) -> String {
+    let mut html = String::from("<html><body>");
+    for (result_num, (course_id, descr)) in results.iter().enumerate() {
+        html.push_str(&format!(
+            "<div><span id='SSR_CLSRCH_ES_FL_CRSE_ID${result_num}'>{course_id}</span>"
+        ));
+        html.push_str(&format!(
+            "<span id='SSR_CLSRCH_ES_FL_SSR_CRSE_DESCR${result_num}'>{descr}</span></div>"
+        ));
+    }
+
+    html.push_str("</body></html>");
+    html
+}
+
+/// Local stand-in for the host, serving generated schedule pages.
+pub mod mock {
+    use std::{
+        collections::HashMap,
+        convert::Infallible,
+        net::SocketAddr,
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use hyper::{
+        body,
+        header::{COOKIE, HOST, LOCATION, SET_COOKIE},
+        service::{make_service_fn, service_fn},
+        Body, Method, Request, Response, Server, StatusCode,
+    };
+
+    /// Form of the `Location` header redirecting to the token.
+    #[derive(Debug, Clone, Copy)]
+    pub enum Redirect {
+        /// Relative to the directory of the current path.
+        Relative,
+        /// Absolute path, with a URL in its query.
+        AbsolutePath,
+        /// Relative to the scheme of the current URL, such as `//host/path`.
+        SchemeRelative,
+    }
+
+    /// Behavior of the mock host.
+    #[derive(Debug, Clone, Copy)]
+    pub struct Mock {
+        groups: &'static [u32],
+        pages_per_token: usize,
+        failures: usize,
+        delay: Duration,
+        post_delay: Duration,
+        body_delay: Duration,
+        token_delay: Duration,
+        redirect: Redirect,
+    }
+
+    impl Mock {
+        /// Each element in `groups` is the amount of classes in that group served by the host.
+        pub fn new(groups: &'static [u32]) -> Self {
+            Self {
+                groups,
+                pages_per_token: usize::MAX,
+                failures: 0,
+                delay: Duration::ZERO,
+                post_delay: Duration::ZERO,
+                body_delay: Duration::ZERO,
+                token_delay: Duration::ZERO,
+                redirect: Redirect::Relative,
+            }
+        }
+
+        /// Expire the first token after the specified amount of page requests.
+        pub fn expire_after(mut self, pages: usize) -> Self {
+            self.pages_per_token = pages;
+            self
+        }
+
+        /// Respond to the specified amount of first page requests with `503 Service Unavailable`.
+        pub fn fail_first(mut self, failures: usize) -> Self {
+            self.failures = failures;
+            self
+        }
+
+        /// Delay every response to a page request.
+        pub fn delay(mut self, delay: Duration) -> Self {
+            self.delay = delay;
+            self
+        }
+
+        /// Further delay every response to a subsequent page request, which is a `POST` request.
+        pub fn delay_posts(mut self, delay: Duration) -> Self {
+            self.post_delay = delay;
+            self
+        }
+
+        /// Delay the body of every response to a page request, after sending its headers.
+        pub fn delay_body(mut self, delay: Duration) -> Self {
+            self.body_delay = delay;
+            self
+        }
+
+        /// Delay every response to a token request once the first token is issued.
+        pub fn delay_tokens(mut self, delay: Duration) -> Self {
+            self.token_delay = delay;
+            self
+        }
+
+        /// Redirect to the token with the specified form of `Location` header.
+        pub fn redirect(mut self, redirect: Redirect) -> Self {
+            self.redirect = redirect;
+            self
+        }
+
+        /// Spawn the server on a random port and return its address.
+        pub fn spawn(self) -> SocketAddr {
+            let state = Arc::new(Mutex::new(State::default()));
+            let make_service = make_service_fn(move |_| {
+                let state = state.clone();
+                async move {
+                    Ok::<_, Infallible>(service_fn(move |request| {
+                        respond(request, self, state.clone())
+                    }))
+                }
+            });
+
+            let server = Server::bind(&([127, 0, 0, 1], 0).into()).serve(make_service);
+            let addr = server.local_addr();
+            tokio::spawn(server);
+
+            addr
+        }
+    }
+
+    #[derive(Debug, Default)]
+    struct State {
+        // Groups viewed by the form of each token, as each token has its own form state
+        groups_viewed: HashMap<u32, usize>,
+        // Incremented every time a new token is issued
+        token_num: u32,
+        // Page requests sent with the first token
+        first_token_pages: usize,
+        // Page requests responded to with a failure
+        failures: usize,
+        // Semester the form of each token was last initialized for
+        semesters: HashMap<u32, String>,
+    }
+
+    /// Spawn a server on a random port and return its address.
+    ///
+    /// Each element in `groups` is the amount of classes in that group served by the host.
+    pub fn spawn(groups: &'static [u32]) -> SocketAddr {
+        Mock::new(groups).spawn()
+    }
+
+    async fn respond(
+        request: Request<Body>,
+        mock: Mock,
+        state: Arc<Mutex<State>>,
+    ) -> Result<Response<Body>, hyper::Error> {
+        let path = request.uri().path().to_owned();
+        let query = request.uri().query().unwrap_or_default().to_owned();
+        let method = request.method().clone();
+        let host = request
+            .headers()
+            .get(HOST)
+            .and_then(|host| host.to_str().ok())
+            .unwrap_or_default()
+            .to_owned();
+        let cookies = request
+            .headers()
+            .get_all(COOKIE)
+            .iter()
+            .filter_map(|cookie| cookie.to_str().ok())
+            .collect::<Vec<_>>()
+            .join("; ");
+        let body = String::from_utf8(body::to_bytes(request.into_body()).await?.to_vec()).unwrap();
+        if path.contains("SSR_CRSE_INFO_FL") {
+            tokio::time::sleep(mock.delay).await;
+            if method == Method::POST {
+                tokio::time::sleep(mock.post_delay).await;
+            }
+        }
+        let token_issued = state.lock().unwrap().token_num > 0;
+        if path.contains("NUI_FRAMEWORK.PT_LANDINGPAGE") && token_issued {
+            tokio::time::sleep(mock.token_delay).await;
+        }
+
+        let mut state = state.lock().unwrap();
+        let response = Response::builder();
+        let param = |name: &str| {
+            query
+                .split('&')
+                .find_map(|pair| pair.strip_prefix(name)?.strip_prefix('='))
+                .map(percent_decode)
+                .unwrap_or_default()
+        };
+        // Every token issued is valid, except the first once it expires
+        let token = cookies
+            .split("; ")
+            .find_map(|cookie| cookie.strip_prefix("psprd-8083-PORTAL-PSJSESSIONID=token"))
+            .and_then(|token| token.parse::<u32>().ok())
+            .filter(|token| (1..=state.token_num).contains(token));
+        if path.starts_with("//") {
+            // The authority of a scheme-relative URL mistaken for part of the path
+            return Ok(response
+                .status(StatusCode::NOT_FOUND)
+                .body(Body::empty())
+                .unwrap());
+        }
+        if path.contains("NUI_FRAMEWORK.PT_LANDINGPAGE") && !query.ends_with('&') {
+            // Redirect to the token, requiring the load balancer cookie
+            let page = "NUI_FRAMEWORK.PT_LANDINGPAGE.GBL?tab=DEFAULT";
+            let location = match mock.redirect {
+                Redirect::Relative => format!("{page}&"),
+                Redirect::AbsolutePath => format!(
+                    "/psc/csprdpub/EMPLOYEE/SA/c/{page}&cmd=login&redirect=https://example.com/&"
+                ),
+                Redirect::SchemeRelative => {
+                    format!("//{host}/psc/csprdpub/EMPLOYEE/SA/c/{page}&")
+                }
+            };
+            return Ok(response
+                .status(StatusCode::FOUND)
+                .header(SET_COOKIE, "BIGipServer=lb1; Path=/")
+                .header(LOCATION, location)
+                .body(Body::empty())
+                .unwrap());
+        } else if path.contains("NUI_FRAMEWORK.PT_LANDINGPAGE") {
+            if !cookies.contains("BIGipServer=lb1") {
+                return Ok(Response::new(Body::empty()));
+            }
+
+            state.token_num += 1;
+            return Ok(response
+                .header(
+                    SET_COOKIE,
+                    format!(
+                        "psprd-8083-PORTAL-PSJSESSIONID=token{}; Path=/; HttpOnly",
+                        state.token_num
+                    ),
+                )
+                .body(Body::empty())
+                .unwrap());
+        } else if path.contains("SSR_CLSRCH_MAIN_FL") {
+            // Only sent with requests for pages
+            return Ok(response
+                .header(SET_COOKIE, "PS_TOKEN=ps1; Path=/psc/csprdpub_3/")
+                .body(Body::from(super::semesters_html(&super::SEMESTERS)))
+                .unwrap());
+        } else if path.contains("SSR_CLSRCH_ES_FL") {
+            // Searching initializes the form for the semester
+            if let Some(token) = token {
+                state.semesters.insert(token, param("ES_STRM"));
+            }
+
+            let text = param("SEARCH_TEXT").to_lowercase();
+            let results: Vec<_> = super::SEARCH_CATALOG
+                .into_iter()
+                .filter(|(_, descr)| descr.to_lowercase().contains(&text))
+                .collect();
+            return Ok(Response::new(Body::from(super::search_html(&results))));
+        } else if !path.contains("SSR_CRSE_INFO_FL") {
+            return Ok(Response::new(Body::empty()));
+        }
+
+        if state.failures < mock.failures {
+            state.failures += 1;
+            return Ok(response
+                .status(StatusCode::SERVICE_UNAVAILABLE)
+                .body(Body::empty())
+                .unwrap());
+        }
+
+        let expired = token == Some(1) && state.first_token_pages >= mock.pages_per_token;
+        let token = match token {
+            Some(token) if cookies.contains("PS_TOKEN=ps1") && !expired => token,
+            _ => {
+                return Ok(Response::new(Body::from(
+                    "<html><body>Your session has expired</body></html>",
+                )))
+            }
+        };
+        if token == 1 {
+            state.first_token_pages += 1;
+        }
+
+        if method == Method::GET && state.semesters.get(&token) != Some(&param("STRM")) {
+            // The form was initialized for another semester
+            return Ok(response
+                .status(StatusCode::BAD_REQUEST)
+                .body(Body::empty())
+                .unwrap());
+        }
+
+        let groups_viewed = state.groups_viewed.entry(token).or_default();
+        if method == Method::GET {
+            *groups_viewed = 50.min(mock.groups.len());
+        } else if body.contains("ICAction=SSR_CLSRCH_F_WK_SSR_SHOW_MORE_PB") {
+            *groups_viewed = (*groups_viewed + 50).min(mock.groups.len());
+        } else {
+            // Phony request
+            return Ok(Response::new(Body::empty()));
+        }
+
+        let html = super::schedule_html(mock.groups, *groups_viewed);
+        if mock.body_delay.is_zero() {
+            return Ok(Response::new(Body::from(html)));
+        }
+
+        let (mut sender, body) = Body::channel();
+        tokio::spawn(async move {
+            tokio::time::sleep(mock.body_delay).await;
+            let _ = sender.send_data(html.into()).await;
+        });
+        Ok(Response::new(body))
+    }
+
+    fn percent_decode(text: &str) -> String {
+        let mut bytes = Vec::new();
+        let mut iter = text.bytes();
+        while let Some(byte) = iter.next() {
+            match byte {
+                b'%' => {
+                    let hex = [iter.next().unwrap(), iter.next().unwrap()];
+                    let hex = std::str::from_utf8(&hex).unwrap();
+                    bytes.push(u8::from_str_radix(hex, 16).unwrap());
+                }
+                _ => bytes.push(byte),
+            }
+        }
+
+        String::from_utf8(bytes).unwrap()
+    }
+}
@@ -1,11 +1,18 @@
 use futures::TryStreamExt;
-use ubs_lib::{Course, Semester};
+use ubs_lib::{Course, Season, Semester};
 
 #[tokio::test]
 async fn schedule_iter() -> Result<(), ubs_lib::ScheduleError> {
-    // TODO: fix when classes in group < 3
-    // let mut schedule_iter = ubs_lib::schedule_iter(Course::Apy106Lec, Semester::Spring2024).await?;
-    let mut schedule_iter = ubs_lib::schedule_iter(Course::Cse115Llr, Semester::Spring2024).await?;
+    print_schedule(Course::Cse115Llr, Semester::new(Season::Spring, 2024)).await
+}
+
+#[tokio::test]
+async fn schedule_iter_small_groups() -> Result<(), ubs_lib::ScheduleError> {
+    print_schedule(Course::Apy106Lec, Semester::new(Season::Spring, 2024)).await
+}
+
+async fn print_schedule(course: Course, semester: Semester) -> Result<(), ubs_lib::ScheduleError> {
+    let mut schedule_iter = ubs_lib::schedule_iter(course, semester).await?;
 
     while let Some(schedule) = schedule_iter.try_next().await? {
         for group in schedule?.group_iter()? {
@@ -0,0 +1,304 @@
+use chrono::NaiveDate;
+use ubs_lib::{Career, Course, Credits, ParseIdError, Season, Semester};
+
+#[test]
+fn semester_ids() -> Result<(), ParseIdError> {
+    let semesters = [
+        (Semester::new(Season::Spring, 2023), "2231"),
+        (Semester::new(Season::Summer, 2023), "2236"),
+        (Semester::new(Season::Fall, 2023), "2239"),
+        (Semester::new(Season::Winter, 2024), "2240"),
+        (Semester::new(Season::Spring, 2024), "2241"),
+        (Semester::new(Season::Fall, 2031), "2319"),
+        (Semester::new(Season::Fall, 1999), "1999"),
+    ];
+    for (semester, id) in semesters {
+        assert_eq!(semester.id(), id);
+        assert_eq!(Semester::from_id(id)?, semester);
+    }
+
+    assert!(Semester::from_id("2242").is_err());
+    assert!(Semester::from_id("224").is_err());
+    assert!(Semester::from_id("0231").is_err());
+
+    Ok(())
+}
+
+#[test]
+fn parse_semester() -> Result<(), ParseIdError> {
+    assert_eq!(
+        "Fall2031".parse::<Semester>()?,
+        Semester::new(Season::Fall, 2031)
+    );
+    assert_eq!(
+        "winter 2024".parse::<Semester>()?,
+        Semester::new(Season::Winter, 2024)
+    );
+
+    assert!("Fall".parse::<Semester>().is_err());
+    assert!("Fall31".parse::<Semester>().is_err());
+    assert!("Autumn2031".parse::<Semester>().is_err());
+
+    Ok(())
+}
+
+#[test]
+fn semester_from_date() {
+    let semester =
+        |year, month, day| Semester::from_date(NaiveDate::from_ymd_opt(year, month, day).unwrap());
+
+    assert_eq!(semester(2024, 1, 10), Semester::new(Season::Winter, 2024));
+    assert_eq!(semester(2024, 3, 1), Semester::new(Season::Spring, 2024));
+    assert_eq!(semester(2024, 6, 15), Semester::new(Season::Summer, 2024));
+    assert_eq!(semester(2024, 12, 31), Semester::new(Season::Fall, 2024));
+}
+
+#[test]
+fn next_semester() {
+    let mut semester = Semester::new(Season::Summer, 2023);
+    let mut order = Vec::new();
+    for _ in 0..4 {
+        semester = semester.next();
+        order.push(semester);
+    }
+
+    assert_eq!(
+        order,
+        [
+            Semester::new(Season::Fall, 2023),
+            Semester::new(Season::Winter, 2024),
+            Semester::new(Season::Spring, 2024),
+            Semester::new(Season::Summer, 2024),
+        ]
+    );
+    assert!(order.windows(2).all(|pair| pair[0] < pair[1]));
+}
+
+#[test]
+fn infer_career() {
+    assert!(matches!(Career::infer("CSE", 115), Career::Undergraduate));
+    assert!(matches!(Career::infer("CSE", 574), Career::Graduate));
+    assert!(matches!(Career::infer("LAW", 505), Career::Law));
+    assert!(matches!(Career::infer("law", 101), Career::Undergraduate));
+    assert!(matches!(Career::infer("PHM", 501), Career::Pharmacy));
+    // Every subject of the dental and medical schools, regardless of catalog number
+    for subject in ["ORB", "ODS", "ORT", "PER", "RSD", "per"] {
+        for catalog_number in [312, 815] {
+            assert!(matches!(
+                Career::infer(subject, catalog_number),
+                Career::DentalMedicine
+            ));
+        }
+    }
+    assert!(matches!(Career::infer("MED", 501), Career::Medicine));
+    assert!(matches!(Career::infer("MED", 101), Career::Medicine));
+
+    assert!(matches!(
+        Career::infer_from_name("cse 574"),
+        Some(Career::Graduate)
+    ));
+    assert!(matches!(
+        Career::infer_from_name("LAW505SEM"),
+        Some(Career::Law)
+    ));
+    assert!(Career::infer_from_name("004229").is_none());
+}
+
+#[test]
+fn catalog_careers() {
+    // The first row of the catalog
+    assert!(matches!(
+        Course::Jpn101Lec.career(),
+        Some(Career::Undergraduate)
+    ));
+    assert!(Course::Raw("004229".to_owned()).career().is_none());
+}
+
+#[test]
+fn parse_course() -> Result<(), ParseIdError> {
+    for name in [
+        "CSE115LLR",
+        "CSE 115 LLR",
+        "cse-115-llr",
+        "CSE115 LLR",
+        "cse 115",
+    ] {
+        assert_eq!(name.parse::<Course>()?.id(), Course::Cse115Llr.id());
+    }
+
+    // Defaults to the lecture
+    assert_eq!("spa 151".parse::<Course>()?.id(), Course::Spa151Lec.id());
+    assert_eq!("SPA151REC".parse::<Course>()?.id(), Course::Spa151Rec.id());
+
+    assert!("CSE 115 LEC".parse::<Course>().is_err());
+    assert!("CSE".parse::<Course>().is_err());
+
+    Ok(())
+}
+
+#[test]
+fn course_parts() {
+    let course = Course::Cse115Llr;
+    assert_eq!(course.subject(), Some("CSE"));
+    assert_eq!(course.catalog_number(), Some("115"));
+    assert_eq!(course.component(), Some("LLR"));
+    assert_eq!(course.to_string(), "CSE 115 LLR");
+
+    let course = Course::Raw("004229".to_owned());
+    assert_eq!(course.subject(), None);
+    assert_eq!(course.to_string(), "004229");
+}
+
+#[test]
+fn course_metadata() {
+    let course = Course::Cse115Llr;
+    assert_eq!(course.title(), Some("Introduction to Computer Science I"));
+    assert_eq!(course.credits(), Some(Credits { min: 4.0, max: 4.0 }));
+    assert!(course.pathways().is_empty());
+    assert_eq!(
+        course.department(),
+        Some("Computer Science and Engineering")
+    );
+
+    // Metadata is optional
+    let course = Course::Apy106Lec;
+    assert_eq!(course.title(), None);
+    assert_eq!(course.credits(), None);
+
+    let course = Course::Raw("004229".to_owned());
+    assert_eq!(course.title(), None);
+    assert!(course.pathways().is_empty());
+}
+
+#[test]
+fn parse_credits() -> Result<(), ParseIdError> {
+    let credits: Credits = "4".parse()?;
+    assert!(!credits.is_variable());
+    assert_eq!(credits.to_string(), "4");
+
+    let credits: Credits = "1 - 3".parse()?;
+    assert_eq!(credits, Credits { min: 1.0, max: 3.0 });
+    assert_eq!(credits.to_string(), "1-3");
+
+    assert_eq!("0.5".parse::<Credits>()?.min, 0.5);
+    assert!("3-1".parse::<Credits>().is_err());
+    assert!("four".parse::<Credits>().is_err());
+
+    Ok(())
+}
+
+#[test]
+fn course_suggestions() {
+    let ids = |name| -> Vec<_> {
+        Course::suggestions(name)
+            .iter()
+            .map(|course| course.id().to_owned())
+            .collect()
+    };
+
+    // Mistyped component
+    assert_eq!(ids("CSE 115 LEC")[0], Course::Cse115Llr.id());
+    assert_eq!(
+        ids("SPA151RC")[..2],
+        [Course::Spa151Rec.id(), Course::Spa151Lec.id()]
+    );
+    assert!(ids("XYZ").is_empty());
+
+    // Input too long to be similar to any course
+    assert!(ids(&"CSE115".repeat(100)).is_empty());
+
+    match "cse115llc".parse::<Course>() {
+        Err(err @ ParseIdError::InvalidCourse { .. }) => {
+            assert!(matches!(&err, ParseIdError::InvalidCourse { given } if given == "cse115llc"));
+            assert_eq!(err.suggestions()[0].id(), Course::Cse115Llr.id());
+        }
+        result => panic!("unexpected result {result:?}"),
+    }
+}
+
+#[test]
+fn course_from_id() {
+    assert!(matches!(Course::from_id("004544"), Course::Cse115Llr));
+    assert!(matches!(Course::from_id("001464"), Course::Apy106Lec));
+    assert!(matches!(Course::from_id("999999"), Course::Raw(id) if id == "999999"));
+
+    for course in Course::ALL {
+        assert_eq!(Course::from_id(course.id()).id(), course.id());
+    }
+}
+
+#[test]
+fn display() {
+    assert_eq!(Semester::new(Season::Fall, 2031).to_string(), "Fall 2031");
+    assert_eq!(Season::Winter.to_string(), "Winter");
+    assert_eq!(Career::DentalMedicine.to_string(), "Dental Medicine");
+    assert_eq!(Career::Raw("ABC".to_owned()).to_string(), "ABC");
+
+    // Display is parsed back into the same value
+    let semester = Semester::new(Season::Spring, 2024);
+    assert_eq!(semester.to_string().parse::<Semester>().unwrap(), semester);
+    assert_eq!(
+        Career::DentalMedicine
+            .to_string()
+            .parse::<Career>()
+            .unwrap(),
+        Career::DentalMedicine
+    );
+}
+
+#[test]
+fn equality() {
+    use std::collections::HashSet;
+
+    // Compared by id
+    assert_eq!(Course::Raw("004544".to_owned()), Course::Cse115Llr);
+    assert_ne!(Course::Cse115Llr, Course::Apy106Lec);
+    assert_eq!(Career::Raw("UGRD".to_owned()), Career::Undergraduate);
+
+    let courses: HashSet<_> = [
+        Course::Cse115Llr,
+        Course::Raw("004544".to_owned()),
+        Course::Apy106Lec,
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(courses.len(), 2);
+}
+
+#[cfg(feature = "serde_support")]
+#[test]
+fn serde() -> Result<(), serde_json::Error> {
+    let json = serde_json::to_string(&(
+        Course::Cse115Llr,
+        Course::Raw("999999".to_owned()),
+        Semester::new(Season::Spring, 2024),
+        Career::DentalMedicine,
+        Career::Raw("ABC".to_owned()),
+    ))?;
+    assert_eq!(
+        json,
+        r#"["CSE115LLR","999999","Spring2024","DentalMedicine","ABC"]"#
+    );
+
+    let (course, raw, semester, career, raw_career): (Course, Course, Semester, Career, Career) =
+        serde_json::from_str(&json)?;
+    assert!(matches!(course, Course::Cse115Llr));
+    assert!(matches!(raw, Course::Raw(id) if id == "999999"));
+    assert_eq!(semester, Semester::new(Season::Spring, 2024));
+    assert!(matches!(career, Career::DentalMedicine));
+    assert!(matches!(raw_career, Career::Raw(id) if id == "ABC"));
+
+    // Parsed the same as `FromStr`, or from ids
+    let (course, semester, career): (Course, Semester, Career) =
+        serde_json::from_str(r#"["cse 115", "2241", "UGRD"]"#)?;
+    assert!(matches!(course, Course::Cse115Llr));
+    assert_eq!(semester, Semester::new(Season::Spring, 2024));
+    assert!(matches!(career, Career::Undergraduate));
+    let (course, _): (Course, Semester) = serde_json::from_str(r#"["004544", "Fall 2024"]"#)?;
+    assert!(matches!(course, Course::Cse115Llr));
+
+    assert!(serde_json::from_str::<Course>(r#""CSE 115 LEC""#).is_err());
+    assert!(serde_json::from_str::<Semester>(r#""Fall""#).is_err());
+
+    Ok(())
+}
@@ -0,0 +1,402 @@
+use std::{
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
+
+use common::mock::{Mock, Redirect};
+use futures::TryStreamExt;
+use hyper::{client::HttpConnector, Client, StatusCode};
+use ubs_lib::{
+    parser::{ClassSchedule, SearchResults, SemesterList},
+    session::{Endpoints, Query, RateLimit, RetryPolicy, Session, SessionError, Token},
+    Career, Course, Season, Semester,
+};
+
+mod common;
+
+const GROUPS: [u32; 120] = [3; 120];
+// Parsing every page into a model is slow in debug builds
+const SMALL_GROUPS: [u32; 60] = [1; 60];
+
+#[tokio::test]
+async fn schedule_iter() -> Result<(), ubs_lib::ScheduleError> {
+    let addr = common::mock::spawn(&GROUPS);
+    assert_eq!(count_groups(addr).await?, GROUPS.len());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn refresh_expired_token() -> Result<(), ubs_lib::ScheduleError> {
+    // Expires in the middle of requesting the second page
+    let addr = Mock::new(&GROUPS).expire_after(2).spawn();
+    assert_eq!(count_groups(addr).await?, GROUPS.len());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn token_redirect_with_url_in_query() -> Result<(), ubs_lib::ScheduleError> {
+    let addr = Mock::new(&GROUPS).redirect(Redirect::AbsolutePath).spawn();
+    let session = session(addr).await?;
+
+    let mut pages = session.schedule_iter(query());
+    assert!(pages.try_next().await?.is_some());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn token_redirect_scheme_relative() -> Result<(), ubs_lib::ScheduleError> {
+    let addr = Mock::new(&GROUPS)
+        .redirect(Redirect::SchemeRelative)
+        .spawn();
+    let session = session(addr).await?;
+
+    let mut pages = session.schedule_iter(query());
+    assert!(pages.try_next().await?.is_some());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn retry_unavailable() -> Result<(), ubs_lib::ScheduleError> {
+    let addr = Mock::new(&GROUPS).fail_first(2).spawn();
+    let session = session(addr).await?.with_retry_policy(retry_policy(3));
+
+    let mut pages = session.schedule_iter(query());
+    assert!(pages.try_next().await?.is_some());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn retry_exhausted() -> Result<(), ubs_lib::ScheduleError> {
+    let addr = Mock::new(&GROUPS).fail_first(2).spawn();
+    let session = session(addr).await?.with_retry_policy(retry_policy(2));
+
+    let mut pages = session.schedule_iter(query());
+    assert!(matches!(
+        pages.try_next().await,
+        Err(SessionError::UnexpectedStatus(
+            StatusCode::SERVICE_UNAVAILABLE
+        ))
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn pagination_not_retried_after_timeout() -> Result<(), ubs_lib::ScheduleError> {
+    let addr = Mock::new(&GROUPS)
+        .delay_posts(Duration::from_millis(500))
+        .spawn();
+    let session = session(addr)
+        .await?
+        .with_retry_policy(RetryPolicy {
+            base_delay: Duration::from_secs(1),
+            ..retry_policy(3)
+        })
+        .with_request_timeout(Some(Duration::from_millis(50)));
+
+    let mut pages = session.schedule_iter(query());
+    assert!(pages.try_next().await?.is_some());
+
+    // Retrying would wait for the base delay, resending the form state
+    let start = Instant::now();
+    assert!(matches!(pages.try_next().await, Err(SessionError::Timeout)));
+    assert!(start.elapsed() < Duration::from_secs(1));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn request_timeout() -> Result<(), ubs_lib::ScheduleError> {
+    let addr = Mock::new(&GROUPS).delay(Duration::from_millis(500)).spawn();
+    let session = session(addr)
+        .await?
+        .with_retry_policy(RetryPolicy::none())
+        .with_request_timeout(Some(Duration::from_millis(50)));
+
+    let mut pages = session.schedule_iter(query());
+    assert!(matches!(pages.try_next().await, Err(SessionError::Timeout)));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn request_timeout_reading_body() -> Result<(), ubs_lib::ScheduleError> {
+    let addr = Mock::new(&GROUPS)
+        .delay_body(Duration::from_millis(500))
+        .spawn();
+    let session = session(addr)
+        .await?
+        .with_retry_policy(RetryPolicy::none())
+        .with_request_timeout(Some(Duration::from_millis(50)));
+
+    // The headers are received in time, though the body isn't
+    let mut pages = session.schedule_iter(query());
+    assert!(matches!(pages.try_next().await, Err(SessionError::Timeout)));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn token_timeout() -> Result<(), ubs_lib::ScheduleError> {
+    let addr = Mock::new(&GROUPS)
+        .delay_tokens(Duration::from_millis(500))
+        .spawn();
+    let session = session(addr)
+        .await?
+        .with_retry_policy(RetryPolicy::none())
+        .with_request_timeout(Some(Duration::from_millis(50)));
+
+    assert!(matches!(
+        session.fork(&Semester::new(Season::Spring, 2024)).await,
+        Err(SessionError::Timeout)
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn total_timeout() -> Result<(), ubs_lib::ScheduleError> {
+    let addr = Mock::new(&GROUPS).fail_first(usize::MAX).spawn();
+    let session = session(addr)
+        .await?
+        .with_retry_policy(retry_policy(u32::MAX))
+        .with_total_timeout(Some(Duration::from_millis(100)));
+
+    let mut pages = session.schedule_iter(query());
+    assert!(matches!(pages.try_next().await, Err(SessionError::Timeout)));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn rate_limit_requests_per_second() -> Result<(), ubs_lib::ScheduleError> {
+    let addr = common::mock::spawn(&GROUPS);
+    let session = session(addr).await?.with_rate_limit(Some(RateLimit {
+        requests_per_second: 20.0,
+        max_concurrent: usize::MAX,
+    }));
+
+    // 1 request for the first page and 2 for each page after
+    let start = Instant::now();
+    let pages: Vec<_> = session.schedule_iter(query()).try_collect().await?;
+    assert_eq!(pages.len(), 3);
+    assert!(start.elapsed() >= Duration::from_millis(4 * 50));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn rate_limit_token() -> Result<(), ubs_lib::ScheduleError> {
+    let addr = common::mock::spawn(&GROUPS);
+    let session = session(addr).await?.with_rate_limit(Some(RateLimit {
+        requests_per_second: 20.0,
+        max_concurrent: usize::MAX,
+    }));
+
+    // 2 requests for the token and 2 to initialize the forked session
+    let start = Instant::now();
+    session.fork(&Semester::new(Season::Spring, 2024)).await?;
+    assert!(start.elapsed() >= Duration::from_millis(3 * 50));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn rate_limit_shared_between_streams() -> Result<(), ubs_lib::ScheduleError> {
+    let addr = Mock::new(&GROUPS).delay(Duration::from_millis(100)).spawn();
+    let session = session(addr).await?.with_rate_limit(Some(RateLimit {
+        requests_per_second: f64::INFINITY,
+        max_concurrent: 1,
+    }));
+
+    // Both first pages must be requested one after the other
+    let start = Instant::now();
+    let mut first = Box::pin(session.schedule_iter(query()));
+    let mut second = Box::pin(session.clone().schedule_iter(query()));
+    let (first, second) = futures::join!(first.try_next(), second.try_next());
+    assert!(first?.is_some() && second?.is_some());
+    assert!(start.elapsed() >= Duration::from_millis(2 * 100));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn fetch_queries() -> Result<(), ubs_lib::ScheduleError> {
+    let addr = common::mock::spawn(&SMALL_GROUPS);
+    let session = session(addr).await?.with_rate_limit(None);
+
+    let courses = [Course::Cse115Llr, Course::Apy106Lec];
+    let queries = courses.iter().map(|course| {
+        Query::new(
+            course.clone(),
+            Semester::new(Season::Spring, 2024),
+            Career::Undergraduate,
+        )
+    });
+    let schedules: Vec<_> = ubs_lib::fetch_queries(&session, queries, 2)
+        .try_collect()
+        .await?;
+
+    assert_eq!(schedules.len(), courses.len());
+    for course in &courses {
+        let (_, schedule) = schedules
+            .iter()
+            .find(|(fetched, _)| fetched == course)
+            .unwrap();
+        assert_eq!(schedule.groups.len(), SMALL_GROUPS.len());
+        assert_eq!(schedule.course_id.as_deref(), Some(course.id()));
+        assert_eq!(schedule.semester, Some(Semester::new(Season::Spring, 2024)));
+        assert_eq!((schedule.page_num, schedule.total_pages), (2, 2));
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn fetch_queries_many_semesters() -> Result<(), ubs_lib::ScheduleError> {
+    let addr = common::mock::spawn(&SMALL_GROUPS);
+    let session = session(addr).await?.with_rate_limit(None);
+
+    // The session is initialized for the spring, though half the queries are for the fall
+    let semesters = [
+        Semester::new(Season::Fall, 2024),
+        Semester::new(Season::Spring, 2024),
+    ];
+    let queries = semesters
+        .iter()
+        .cycle()
+        .take(6)
+        .map(|semester| Query::new(Course::Cse115Llr, *semester, Career::Undergraduate));
+    let schedules: Vec<_> = ubs_lib::fetch_queries(&session, queries, 2)
+        .try_collect()
+        .await?;
+    assert_eq!(schedules.len(), 6);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn fork() -> Result<(), ubs_lib::ScheduleError> {
+    let addr = common::mock::spawn(&GROUPS);
+    let session = session(addr).await?;
+    let forked = session.fork(&Semester::new(Season::Spring, 2024)).await?;
+
+    // Pages are interleaved without replacing each other's form state
+    let mut first = Box::pin(session.schedule_iter(query()));
+    let mut second = Box::pin(forked.schedule_iter(query()));
+    for page_num in 1..=3 {
+        for pages in [&mut first, &mut second] {
+            let page = pages.try_next().await?.unwrap();
+            assert_eq!(ClassSchedule::from_page(page)?.page_num()?, page_num);
+        }
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn search() -> Result<(), ubs_lib::ScheduleError> {
+    let addr = common::mock::spawn(&GROUPS);
+    let session = session(addr).await?;
+
+    let bytes = session
+        .search("cse 11", &Semester::new(Season::Spring, 2024))
+        .await?;
+    let results = SearchResults::new(bytes.into())?.model()?;
+    let ids: Vec<_> = results
+        .iter()
+        .map(|result| result.course().id().to_owned())
+        .collect();
+    assert_eq!(ids, ["004229", "004230"]);
+
+    let bytes = session
+        .search("machine learning", &Semester::new(Season::Spring, 2024))
+        .await?;
+    let results = SearchResults::new(bytes.into())?.model()?;
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].catalog_number, "474");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn semesters() -> Result<(), ubs_lib::ScheduleError> {
+    let addr = common::mock::spawn(&GROUPS);
+    let endpoints = Endpoints::new(&format!("http://{addr}"));
+    let client = Client::new();
+
+    // The session doesn't need to be initialized
+    let token = Token::with_endpoints(&client, &endpoints).await?;
+    let session = Session::new(client, token).with_endpoints(endpoints);
+
+    let bytes = session.semesters().await?;
+    let semesters = SemesterList::new(bytes.into())?.semesters()?;
+    let ids: Vec<_> = semesters
+        .iter()
+        .map(|(semester, _)| semester.id().to_owned())
+        .collect();
+    assert_eq!(ids, ["2241", "2246", "2249"]);
+
+    Ok(())
+}
+
+/// Retry policy with a short delay.
+fn retry_policy(max_attempts: u32) -> RetryPolicy {
+    RetryPolicy {
+        max_attempts,
+        base_delay: Duration::from_millis(1),
+        jitter: false,
+        ..Default::default()
+    }
+}
+
+fn query() -> Query {
+    Query::new(
+        Course::Cse115Llr,
+        Semester::new(Season::Spring, 2024),
+        Career::Undergraduate,
+    )
+}
+
+/// Create and initialize a session to the mock host.
+async fn session(addr: SocketAddr) -> Result<Session<Client<HttpConnector>>, SessionError> {
+    let endpoints = Endpoints::new(&format!("http://{addr}"));
+    let client = Client::new();
+
+    let token = Token::with_endpoints(&client, &endpoints).await?;
+    let session = Session::new(client, token).with_endpoints(endpoints);
+    session
+        .initialize(&Semester::new(Season::Spring, 2024))
+        .await?;
+
+    Ok(session)
+}
+
+/// Count the groups of every page, asserting that pages are in order.
+async fn count_groups(addr: SocketAddr) -> Result<usize, ubs_lib::ScheduleError> {
+    let session = session(addr).await?;
+    let mut pages = session.schedule_iter(query());
+
+    let mut num_pages = 0;
+    let mut num_groups = 0;
+    while let Some(page) = pages.try_next().await? {
+        let fetched_at = page.fetched_at;
+        let schedule = ClassSchedule::from_page(page)?;
+        // Modeling the page again keeps the time it was fetched
+        let (first, second) = (schedule.model()?, schedule.model()?);
+        assert_eq!(first.fetched_at, Some(fetched_at));
+        assert_eq!(second.fetched_at, first.fetched_at);
+        num_pages += 1;
+        assert_eq!(schedule.page_num()?, num_pages);
+        num_groups += schedule.group_iter()?.count();
+    }
+
+    assert_eq!(num_pages, 3);
+
+    Ok(num_groups)
+}
@@ -0,0 +1,255 @@
+use ubs_lib::{
+    parser::{
+        ClassSchedule, ClassType, Instructor, InstructorName, ParseError, SearchResults,
+        SeatStatus, SemesterList,
+    },
+    session::Query,
+    Career, Course, Credits, Season, Semester,
+};
+
+mod common;
+
+#[test]
+fn varying_classes_per_group() -> Result<(), ParseError> {
+    let groups = [1, 3, 5];
+    let schedule = ClassSchedule::new(common::schedule_html(&groups, groups.len()).into_bytes())?;
+
+    let mut num_groups = 0;
+    for (group, &expected) in schedule.group_iter()?.zip(groups.iter()) {
+        assert_eq!(group.num_classes(), expected);
+        assert_eq!(group.class_iter().count(), expected as usize);
+
+        for class in group.class_iter() {
+            class.class_id()?;
+            class.start_time()?.unwrap();
+            class.instructor()?;
+        }
+        num_groups += 1;
+    }
+    assert_eq!(num_groups, groups.len());
+
+    let group = schedule.group_from_index(2);
+    assert!(matches!(
+        group.class_from_index(4).class_type()?,
+        ClassType::Recitation
+    ));
+    assert!(group.class_from_index(5).class_id().is_err());
+
+    Ok(())
+}
+
+#[test]
+fn pages() -> Result<(), ParseError> {
+    let groups = [3; 120];
+
+    let schedule = ClassSchedule::new(common::schedule_html(&groups, 100).into_bytes())?;
+    assert_eq!(schedule.total_pages()?, 3);
+    assert_eq!(schedule.page_num()?, 2);
+    // Only the groups of the current page are iterated
+    assert_eq!(schedule.group_iter()?.count(), 50);
+
+    let schedule = ClassSchedule::new(common::schedule_html(&groups, 120).into_bytes())?;
+    assert_eq!(schedule.page_num()?, 3);
+    assert_eq!(schedule.group_iter()?.count(), 20);
+
+    Ok(())
+}
+
+#[test]
+fn course_details() -> Result<(), ParseError> {
+    let schedule = ClassSchedule::new(common::schedule_html(&[3], 1).into_bytes())?;
+    assert_eq!(schedule.title()?, "Introduction to Computer Science I");
+    assert_eq!(
+        schedule.description()?,
+        "Introduces computer science through programming."
+    );
+    assert_eq!(schedule.units()?, Credits { min: 4.0, max: 4.0 });
+    assert_eq!(schedule.grading_basis()?, "Graded");
+    assert_eq!(schedule.requirements()?, "Corequisite: MTH 141");
+
+    // Details are optional
+    let schedule = ClassSchedule::new(b"<html><body></body></html>".to_vec())?;
+    assert!(matches!(
+        schedule.requirements(),
+        Err(ParseError::MissingTag { .. })
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn schedule_model() -> Result<(), ParseError> {
+    let groups = [1; 60];
+    let schedule = ClassSchedule::new(common::schedule_html(&groups, 60).into_bytes())?;
+
+    let model = schedule.model()?;
+    assert_eq!(model.semester, Some(Semester::new(Season::Spring, 2024)));
+    assert_eq!((model.page_num, model.total_pages), (2, 2));
+    assert_eq!(model.groups.len(), 10);
+    assert_eq!(model.units, Some(Credits { min: 4.0, max: 4.0 }));
+    assert_eq!(model.grading_basis.as_deref(), Some("Graded"));
+    // Only known from the query
+    assert_eq!(model.course_id, None);
+    assert_eq!(model.career, None);
+
+    let query = Query::new(
+        Course::Cse115Llr,
+        Semester::new(Season::Spring, 2024),
+        Career::Undergraduate,
+    );
+    let model = model.with_query(&query);
+    assert_eq!(model.course_id.as_deref(), Some("004544"));
+    assert_eq!(model.course_name.as_deref(), Some("CSE 115 LLR"));
+    assert_eq!(model.career, Some(Career::Undergraduate));
+
+    Ok(())
+}
+
+#[test]
+fn seat_status() -> Result<(), ParseError> {
+    let statuses = [
+        (
+            "Open Seats 5 of 30",
+            SeatStatus::Open { open: 5, total: 30 },
+        ),
+        ("Closed", SeatStatus::Closed),
+        (
+            "Wait List 3 of 10",
+            SeatStatus::Waitlist {
+                taken: 3,
+                capacity: 10,
+            },
+        ),
+        ("Reserved Seats", SeatStatus::Reserved),
+        ("Cancelled", SeatStatus::Unknown("Cancelled".to_owned())),
+    ];
+
+    for (text, status) in statuses {
+        let html = common::schedule_html(&[1], 1).replace("Open Seats 5 of 30", text);
+        let schedule = ClassSchedule::new(html.into_bytes())?;
+        let group = schedule.group_iter()?.next().unwrap();
+        let class = group.class_iter().next().unwrap();
+
+        let is_open = matches!(status, SeatStatus::Open { .. });
+        assert_eq!(class.is_open()?, is_open);
+        assert_eq!(class.seat_status()?, status);
+        assert_eq!(status.is_open(), is_open);
+        assert_eq!(class.open_seats()?, status.open_seats());
+
+        // Seats of the model are derived from the same status
+        let class = &schedule.model()?.groups[0].classes[0];
+        assert_eq!(class.is_open, Some(is_open));
+        assert_eq!(
+            (class.open_seats, class.total_seats),
+            (status.open_seats(), status.total_seats())
+        );
+    }
+
+    let model = ClassSchedule::new(common::schedule_html(&[1], 1).into_bytes())?.model()?;
+    let class = &model.groups[0].classes[0];
+    assert_eq!(
+        class.seat_status,
+        Some(SeatStatus::Open { open: 5, total: 30 })
+    );
+    assert_eq!((class.open_seats, class.total_seats), (Some(5), Some(30)));
+    // Raw bytes weren't fetched by a session
+    assert_eq!(model.fetched_at, None);
+
+    Ok(())
+}
+
+#[test]
+fn instructor() -> Result<(), ParseError> {
+    let name = |first: &str, last: &str| InstructorName {
+        first: first.to_owned(),
+        last: last.to_owned(),
+    };
+    let instructors = [
+        ("Doe,Jane", Instructor::Named(vec![name("Jane", "Doe")])),
+        ("Doe, Jane", Instructor::Named(vec![name("Jane", "Doe")])),
+        ("Jane  Doe", Instructor::Named(vec![name("Jane", "Doe")])),
+        ("To be Announced", Instructor::Tba),
+        (
+            "Doe,Jane<br />Smith,John Paul",
+            Instructor::Named(vec![name("Jane", "Doe"), name("John Paul", "Smith")]),
+        ),
+        (
+            "Doe,Jane, Van Buren,Martin",
+            Instructor::Named(vec![name("Jane", "Doe"), name("Martin", "Van Buren")]),
+        ),
+        (
+            "Jane Doe, John Smith",
+            Instructor::Named(vec![name("Jane", "Doe"), name("John", "Smith")]),
+        ),
+    ];
+
+    for (text, instructor) in instructors {
+        let html = common::schedule_html(&[1], 1).replace("Doe,Jane", text);
+        let schedule = ClassSchedule::new(html.into_bytes())?;
+        let group = schedule.group_iter()?.next().unwrap();
+        assert_eq!(group.class_iter().next().unwrap().instructor()?, instructor);
+    }
+
+    let instructor: Instructor = "Doe,Jane; Smith,John".parse()?;
+    assert_eq!(instructor.to_string(), "Jane Doe, John Smith");
+    assert_eq!(instructor.names().len(), 2);
+    assert!(Instructor::Tba.names().is_empty());
+    assert_eq!(Instructor::Tba.to_string(), "To be Announced");
+    assert!("".parse::<Instructor>().is_err());
+
+    let model = ClassSchedule::new(common::schedule_html(&[1], 1).into_bytes())?.model()?;
+    assert_eq!(
+        model.groups[0].classes[0].instructor,
+        Some(Instructor::Named(vec![name("Jane", "Doe")]))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn search_results() -> Result<(), ParseError> {
+    let results = SearchResults::new(common::search_html(&common::SEARCH_CATALOG).into_bytes())?;
+    assert_eq!(results.num_results(), 3);
+
+    let result = results.result_from_index(2);
+    assert_eq!(result.course_id()?, "004511");
+    assert_eq!(result.subject()?, "CSE");
+    assert_eq!(result.catalog_number()?, "474");
+    assert_eq!(result.title()?, "Introduction to Machine Learning");
+    assert_eq!(result.course()?.id(), "004511");
+
+    let empty = SearchResults::new(common::search_html(&[]).into_bytes())?;
+    assert_eq!(empty.result_iter().count(), 0);
+
+    Ok(())
+}
+
+#[test]
+fn semester_list() -> Result<(), ParseError> {
+    let list = SemesterList::new(common::semesters_html(&common::SEMESTERS).into_bytes())?;
+    let semesters = list.semesters()?;
+    assert_eq!(semesters.len(), 3);
+
+    assert_eq!(semesters[0].0, Semester::new(Season::Spring, 2024));
+    assert_eq!(semesters[0].1, "Spring 2024");
+    assert_eq!(semesters[2].0, Semester::new(Season::Fall, 2024));
+    assert_eq!(semesters[2].1, "Fall 2024");
+
+    // Options with unknown term codes are skipped
+    let list = SemesterList::new(
+        common::semesters_html(&[
+            ("2241", "Spring 2024"),
+            ("LATE", "Late Registration"),
+            ("2249", "Fall 2024"),
+        ])
+        .into_bytes(),
+    )?;
+    let ids: Vec<_> = list
+        .semesters()?
+        .iter()
+        .map(|(semester, _)| semester.id())
+        .collect();
+    assert_eq!(ids, ["2241", "2249"]);
+
+    Ok(())
+}
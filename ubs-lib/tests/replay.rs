@@ -0,0 +1,90 @@
+use futures::TryStreamExt;
+use hyper::Client;
+use ubs_lib::{
+    parser::ClassSchedule,
+    session::{Endpoints, Query, Session, Token},
+    transport::{RecordingTransport, ReplayTransport, Transport},
+    Career, Course, Season, Semester,
+};
+
+mod common;
+
+const FIXTURE_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/cse115llr");
+const TIMEOUT_SCRIPT_FIXTURE_DIR: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/fixtures/cse115llr_timeout_script"
+);
+const GROUPS: [u32; 60] = [2; 60];
+
+#[tokio::test]
+async fn replay() -> Result<(), ubs_lib::ScheduleError> {
+    let transport = ReplayTransport::new(FIXTURE_DIR).unwrap();
+    let class_ids = class_ids(transport, Endpoints::default()).await?;
+
+    assert_eq!(class_ids.len(), 1 + 3 + 5);
+    assert_eq!(class_ids[0], 10000);
+
+    Ok(())
+}
+
+// Synthetic pages with a timeout script referencing the expiry URL and message, as PeopleSoft
+// includes in every page, rather than a recording of the host
+#[tokio::test]
+async fn replay_page_with_timeout_script() -> Result<(), ubs_lib::ScheduleError> {
+    let transport = ReplayTransport::new(TIMEOUT_SCRIPT_FIXTURE_DIR).unwrap();
+    let class_ids = class_ids(transport, Endpoints::default()).await?;
+
+    assert_eq!(class_ids.len(), 1 + 3 + 5);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn record_and_replay() -> Result<(), ubs_lib::ScheduleError> {
+    let dir = std::env::temp_dir().join(format!("ubs-fixtures-{}", std::process::id()));
+    let addr = common::mock::spawn(&GROUPS);
+
+    let transport = RecordingTransport::new(Client::new(), &dir).unwrap();
+    let recorded = class_ids(transport, Endpoints::new(&format!("http://{addr}"))).await?;
+    // The host is ignored when replaying
+    let replayed = class_ids(ReplayTransport::new(&dir).unwrap(), Endpoints::default()).await?;
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(recorded.len(), GROUPS.iter().sum::<u32>() as usize);
+    assert_eq!(recorded, replayed);
+
+    Ok(())
+}
+
+/// Fetch the id of every class for the query.
+async fn class_ids<T>(
+    transport: T,
+    endpoints: Endpoints,
+) -> Result<Vec<u32>, ubs_lib::ScheduleError>
+where
+    T: Transport,
+{
+    let token = Token::with_endpoints(&transport, &endpoints).await?;
+    let session = Session::new(transport, token).with_endpoints(endpoints);
+    session
+        .initialize(&Semester::new(Season::Spring, 2024))
+        .await?;
+
+    let mut pages = session.schedule_iter(Query::new(
+        Course::Cse115Llr,
+        Semester::new(Season::Spring, 2024),
+        Career::Undergraduate,
+    ));
+
+    let mut class_ids = Vec::new();
+    while let Some(page) = pages.try_next().await? {
+        let schedule = ClassSchedule::from_page(page)?;
+        for group in schedule.group_iter()? {
+            for class in group.class_iter() {
+                class_ids.push(class.class_id()?);
+            }
+        }
+    }
+
+    Ok(class_ids)
+}
@@ -0,0 +1,284 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs::{self, File},
+    io,
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+use ubs_lib::{catalog::CatalogEntry, Catalog};
+
+use crate::Error;
+
+// All pathways are undergraduate
+const CAREER: &str = "UGRD";
+
+/// Topics export from path-finder.
+#[derive(Debug, Deserialize)]
+struct Topics {
+    data: Vec<Topic>,
+}
+
+/// Pathway along with the courses fulfilling it.
+#[derive(Debug, Deserialize)]
+struct Topic {
+    #[serde(default)]
+    name: String,
+    courses: HashMap<String, Vec<TopicCourse>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TopicCourse {
+    course_id: String,
+    subject: String,
+    catalog_number: String,
+    // Such as `LEC`, required to name new courses the same as the rest of the catalog
+    #[serde(default)]
+    component: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct CourseInfo {
+    id: String,
+    name: String,
+}
+
+impl From<&CatalogEntry> for CourseInfo {
+    fn from(entry: &CatalogEntry) -> Self {
+        Self {
+            id: entry.id.clone(),
+            name: entry.name.clone(),
+        }
+    }
+}
+
+/// Courses added to and removed from the catalog by an import.
+///
+/// New courses without a component are skipped rather than added, as their name would be
+/// inconsistent with every other course in the catalog.
+#[derive(Debug, Serialize)]
+pub struct ImportReport {
+    added: Vec<CourseInfo>,
+    removed: Vec<CourseInfo>,
+    skipped: Vec<CourseInfo>,
+}
+
+/// Merge the courses of the path-finder export into the catalog.
+///
+/// See [`merge`](merge) for details. The catalog is only written if every course is valid.
+pub fn import(input: &Path, catalog_path: &Path, prune: bool) -> Result<ImportReport, Error> {
+    let topics: Topics = serde_json::from_reader(io::BufReader::new(File::open(input)?))?;
+    let catalog = match File::open(catalog_path) {
+        Ok(file) => Catalog::from_csv(file)?,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Catalog::default(),
+        Err(err) => return Err(err.into()),
+    };
+
+    let (entries, report) = merge(catalog.entries(), topics, prune);
+
+    // Validates every course before overwriting the catalog
+    let catalog = Catalog::new(entries)?;
+    let mut csv = Vec::new();
+    catalog.write_csv(&mut csv)?;
+    fs::write(catalog_path, csv)?;
+
+    Ok(report)
+}
+
+/// Merge the courses of the topics into the catalog entries.
+///
+/// Existing courses keep their order and metadata, while new courses are appended sorted by name,
+/// as the catalog itself isn't sorted. The name of each topic is added to the pathways of its
+/// courses. Courses with duplicate ids are removed, along with courses missing from the export if
+/// `prune` is specified, while new courses without a component are skipped.
+fn merge<'a, I>(entries: I, topics: Topics, prune: bool) -> (Vec<CatalogEntry>, ImportReport)
+where
+    I: IntoIterator<Item = &'a CatalogEntry>,
+{
+    let mut imported: HashMap<String, (TopicCourse, Vec<String>)> = HashMap::new();
+    for topic in topics.data {
+        for course in topic.courses.into_values().flatten() {
+            let (_, pathways) = imported
+                .entry(course.course_id.clone())
+                .or_insert_with(|| (course, Vec::new()));
+            if !topic.name.is_empty() && !pathways.contains(&topic.name) {
+                pathways.push(topic.name.clone());
+            }
+        }
+    }
+
+    let mut exists = HashSet::new();
+    let mut existing = Vec::new();
+    let mut removed = Vec::new();
+    for entry in entries {
+        if !exists.insert(entry.id.clone()) || (prune && !imported.contains_key(&entry.id)) {
+            removed.push(CourseInfo::from(entry));
+            continue;
+        }
+
+        let mut entry = entry.clone();
+        if let Some((_, pathways)) = imported.get(&entry.id) {
+            for pathway in pathways {
+                if !entry.pathways.contains(pathway) {
+                    entry.pathways.push(pathway.clone());
+                }
+            }
+        }
+        existing.push(entry);
+    }
+
+    let mut added = Vec::new();
+    let mut skipped = Vec::new();
+    for (course, pathways) in imported.into_values() {
+        if exists.contains(&course.course_id) {
+            continue;
+        }
+
+        let name = format!("{}{}", course.subject, course.catalog_number);
+        match course.component.filter(|component| !component.is_empty()) {
+            Some(component) => added.push(CatalogEntry {
+                id: course.course_id,
+                career: CAREER.to_owned(),
+                name: format!("{name}{component}"),
+                title: None,
+                credits: None,
+                pathways,
+                department: None,
+            }),
+            None => skipped.push(CourseInfo {
+                id: course.course_id,
+                name,
+            }),
+        }
+    }
+    added.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.id.cmp(&b.id)));
+    skipped.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.id.cmp(&b.id)));
+
+    let report = ImportReport {
+        added: added.iter().map(CourseInfo::from).collect(),
+        removed,
+        skipped,
+    };
+
+    let mut merged = existing;
+    merged.extend(added);
+
+    (merged, report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str, name: &str, pathways: &[&str]) -> CatalogEntry {
+        CatalogEntry {
+            id: id.to_owned(),
+            career: CAREER.to_owned(),
+            name: name.to_owned(),
+            title: None,
+            credits: None,
+            pathways: pathways.iter().map(|pathway| pathway.to_string()).collect(),
+            department: None,
+        }
+    }
+
+    fn topics(json: &str) -> Topics {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn ids(entries: &[CatalogEntry]) -> Vec<&str> {
+        entries.iter().map(|entry| entry.id.as_str()).collect()
+    }
+
+    const TOPICS: &str = r#"{"data": [
+        {"name": "Global Pathway", "courses": {"1": [
+            {"course_id": "000002", "subject": "CSE", "catalog_number": "115", "component": "LLR"},
+            {"course_id": "000004", "subject": "APY", "catalog_number": "106", "component": "LEC"}
+        ]}},
+        {"name": "Thematic Pathway", "courses": {"1": [
+            {"course_id": "000004", "subject": "APY", "catalog_number": "106", "component": "LEC"},
+            {"course_id": "000005", "subject": "ZZZ", "catalog_number": "101", "component": "SEM"},
+            {"course_id": "000006", "subject": "ZZZ", "catalog_number": "102"}
+        ], "2": [
+            {"course_id": "000001", "subject": "CSE", "catalog_number": "116"}
+        ]}}
+    ]}"#;
+
+    #[test]
+    fn merge_appended() {
+        // Existing courses aren't sorted, as in the catalog
+        let entries = [
+            entry("000003", "MTH141LEC", &[]),
+            entry("000001", "CSE116LEC", &[]),
+        ];
+        let (merged, report) = merge(&entries, topics(TOPICS), false);
+
+        assert_eq!(
+            ids(&merged),
+            ["000003", "000001", "000004", "000002", "000005"]
+        );
+        assert_eq!(
+            report.added,
+            [
+                CourseInfo::from(&entry("000004", "APY106LEC", &[])),
+                CourseInfo::from(&entry("000002", "CSE115LLR", &[])),
+                CourseInfo::from(&entry("000005", "ZZZ101SEM", &[])),
+            ]
+        );
+        assert!(report.removed.is_empty());
+        // Existing courses don't need a component, as they're already named
+        assert_eq!(
+            report.skipped,
+            [CourseInfo::from(&entry("000006", "ZZZ102", &[]))]
+        );
+    }
+
+    #[test]
+    fn merge_pathways() {
+        let entries = [entry("000001", "CSE116LEC", &["Thematic Pathway", "Other"])];
+        let (merged, _) = merge(&entries, topics(TOPICS), false);
+
+        let pathways = |id| &merged.iter().find(|entry| entry.id == id).unwrap().pathways;
+        // Existing pathways are kept without duplicates
+        assert_eq!(pathways("000001"), &["Thematic Pathway", "Other"]);
+        assert_eq!(pathways("000002"), &["Global Pathway"]);
+        assert_eq!(pathways("000004"), &["Global Pathway", "Thematic Pathway"]);
+    }
+
+    #[test]
+    fn dedup_and_prune() {
+        let entries = [
+            entry("000001", "CSE116LEC", &[]),
+            entry("000001", "CSE116LEC", &[]),
+            entry("000003", "MTH141LEC", &[]),
+        ];
+
+        let (merged, report) = merge(&entries, topics(TOPICS), false);
+        assert_eq!(
+            merged.iter().filter(|entry| entry.id == "000001").count(),
+            1
+        );
+        assert_eq!(ids(&merged).len(), 5);
+        assert_eq!(
+            report.removed,
+            [CourseInfo::from(&entry("000001", "CSE116LEC", &[]))]
+        );
+
+        let (merged, report) = merge(&entries, topics(TOPICS), true);
+        assert!(!ids(&merged).contains(&"000003"));
+        assert_eq!(
+            report.removed,
+            [
+                CourseInfo::from(&entry("000001", "CSE116LEC", &[])),
+                CourseInfo::from(&entry("000003", "MTH141LEC", &[])),
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_catalog() {
+        let (merged, report) = merge(&[], topics(r#"{"data": []}"#), true);
+        assert!(merged.is_empty());
+        assert!(report.added.is_empty() && report.removed.is_empty() && report.skipped.is_empty());
+    }
+}
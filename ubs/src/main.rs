@@ -1,58 +1,130 @@
-use std::str::FromStr;
+use std::{fs::File, path::Path, process::ExitCode, str::FromStr};
 
 use clap::Parser;
 use futures::TryStreamExt;
 use options::Options;
-use ubs_lib::{Career, Course, Semester};
+use serde::Serialize;
+use ubs_lib::{catalog::CatalogError, session::Query, Career, Catalog, Course, Semester};
 
-use crate::options::{DataFormat, Raw};
+use crate::options::{CatalogCommand, Command, DataFormat, Raw};
 
+mod import;
 mod options;
 
+#[derive(Debug, Serialize)]
+struct SemesterInfo {
+    id: String,
+    name: String,
+}
+
 #[tokio::main(flavor = "current_thread")]
-async fn main() -> Result<(), Error> {
+async fn main() -> ExitCode {
     let args = Options::parse();
 
+    // Print the message rather than the debug representation, such as suggestions for courses
+    match run(&args).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(args: &Options) -> Result<(), Error> {
+    match &args.command {
+        Some(Command::Semesters) => {
+            let semesters: Vec<_> = ubs_lib::semesters()
+                .await?
+                .into_iter()
+                .map(|(semester, name)| SemesterInfo {
+                    id: semester.id().to_owned(),
+                    name,
+                })
+                .collect();
+            print(args, &semesters)
+        }
+        Some(Command::Catalog {
+            command:
+                CatalogCommand::Import {
+                    input,
+                    catalog,
+                    prune,
+                },
+        }) => print(args, &import::import(input, catalog, *prune)?),
+        None => schedule(args).await,
+    }
+}
+
+async fn schedule(args: &Options) -> Result<(), Error> {
+    // Both are required unless a subcommand is specified
+    let (Some(course), Some(semester)) = (&args.course, &args.semester) else {
+        unreachable!()
+    };
+
+    let catalog = match &args.catalog {
+        Some(path) => load_catalog(path)?,
+        None => Catalog::default(),
+    };
+
     let course = if args.raw.contains(&Raw::Course) {
-        Ok(Course::Raw(args.course.to_owned()))
+        Ok(Course::from_id(course))
     } else {
-        Course::from_str(&args.course)
+        catalog.course(course).ok_or_else(|| Error::InvalidCourse {
+            given: course.to_owned(),
+            suggestions: catalog.suggestions(course),
+        })
     }?;
     let semester = if args.raw.contains(&Raw::Semester) {
-        Ok(Semester::Raw(args.semester.to_owned()))
+        Semester::from_id(semester)
     } else {
-        Semester::from_str(&args.semester)
+        Semester::from_str(semester)
     }?;
     let career = if args.raw.contains(&Raw::Career) {
         Ok(Career::Raw(
-            args.career.ok_or(Error::RawCareerNotSpecified)?,
+            args.career.clone().ok_or(Error::RawCareerNotSpecified)?,
         ))
     } else {
-        match course.career() {
+        match catalog.career(&course) {
             Some(career) => Ok(career),
-            None => Career::from_str(&args.career.ok_or(Error::CareerNotSpecified)?),
+            None => Career::from_str(args.career.as_ref().ok_or(Error::CareerNotSpecified)?),
         }
     }?;
 
+    let query = Query::new(course.clone(), semester, career.clone());
     let mut schedule_iter = ubs_lib::schedule_iter_with_career(course, semester, career).await?;
     let mut schedules = Vec::new();
 
-    #[allow(clippy::never_loop)] // TODO: temp
     while let Some(schedule) = schedule_iter.try_next().await? {
-        schedules.push(schedule?.model()?);
-        break; // TODO: for now since subsequent pages aren't implemented
+        schedules.push(schedule?.model()?.with_query(&query));
     }
 
+    print(args, &schedules)
+}
+
+/// Load a catalog from a JSON file if it has a `.json` extension, otherwise from a CSV file.
+fn load_catalog(path: &Path) -> Result<Catalog, Error> {
+    let file = File::open(path)?;
+    let catalog = match path.extension().and_then(|extension| extension.to_str()) {
+        Some(extension) if extension.eq_ignore_ascii_case("json") => Catalog::from_json(file),
+        _ => Catalog::from_csv(file),
+    }?;
+
+    Ok(catalog)
+}
+
+/// Print the value in the specified output format.
+fn print<T: Serialize>(args: &Options, value: &T) -> Result<(), Error> {
     let result = match args.format {
         DataFormat::Json => match args.pretty {
-            true => serde_json::to_string_pretty(&schedules)?,
-            false => serde_json::to_string(&schedules)?,
+            true => serde_json::to_string_pretty(value)?,
+            false => serde_json::to_string(value)?,
         },
     };
 
     #[cfg(feature = "color")]
     let result = match args.pretty {
-        true => highlight_syntax(args.format, &result),
+        true => highlight_syntax(args.format.clone(), &result),
         false => result,
     };
 
@@ -97,9 +169,33 @@ pub enum Error {
     #[error(transparent)]
     FailedToInferId(#[from] ubs_lib::ParseIdError),
     #[error(transparent)]
+    InvalidCatalog(#[from] CatalogError),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
     JsonSerializeFailed(#[from] serde_json::Error),
+    #[error("`{given}` is an invalid `Course`{}", suggestions_message(.suggestions))]
+    InvalidCourse {
+        given: String,
+        suggestions: Vec<Course>,
+    },
     #[error("career not specified with `--raw` argument passed")]
     RawCareerNotSpecified,
     #[error("career could not be inferred and was not specified, consider specifying the career")]
     CareerNotSpecified,
 }
+
+/// Format suggestions for [`Error::InvalidCourse`](Error::InvalidCourse).
+fn suggestions_message(suggestions: &[Course]) -> String {
+    match suggestions {
+        [] => String::new(),
+        [suggestion] => format!(", did you mean `{suggestion}`?"),
+        suggestions => {
+            let suggestions: Vec<_> = suggestions
+                .iter()
+                .map(|suggestion| format!("`{suggestion}`"))
+                .collect();
+            format!(", did you mean one of {}?", suggestions.join(", "))
+        }
+    }
+}
@@ -1,26 +1,74 @@
-use clap::{Parser, ValueEnum};
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand, ValueEnum};
 
 #[derive(Debug, Parser)]
-#[command(author, version, about)]
+#[command(
+    author,
+    version,
+    about,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
 pub struct Options {
-    /// Course name and number to query (e.g. CSE115, GLY105) or course id (e.g. 004544)
-    pub course: String,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    /// Course subject, number, and optionally component to query (e.g. CSE115, "CSE 115 LLR", gly-105)
+    /// or course id (e.g. 004544)
+    #[clap(required = true)]
+    pub course: Option<String>,
     /// Semester to query (e.g. Spring2023, Summer2023, Fall2023, Winter2023) or semester id (e.g. 2231)
-    pub semester: String,
+    #[clap(required = true)]
+    pub semester: Option<String>,
     /// Career to query (e.g Undergraduate, Graduate, Law, DentalMedicine, Medicine, Pharmacy) or
     /// career id (e.g. SDM)
     pub career: Option<String>,
+    /// Catalog of courses to load in addition to the built-in catalog, in CSV or JSON format
+    /// (e.g. courses.csv)
+    #[clap(long)]
+    pub catalog: Option<PathBuf>,
     /// Format to output data
-    #[clap(long, value_enum, default_value_t = DataFormat::Json)]
+    #[clap(long, value_enum, default_value_t = DataFormat::Json, global = true)]
     pub format: DataFormat,
     /// Pretty print output format
-    #[clap(long)]
+    #[clap(long, global = true)]
     pub pretty: bool,
     /// Arguments that don't infer value, rather use the raw id
     #[clap(long, num_args = 1)]
     pub raw: Vec<Raw>,
 }
 
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// List the semesters currently offered, along with their ids
+    // Hidden until the page is verified against a recording of the host
+    #[command(hide = true)]
+    Semesters,
+    /// Maintain a catalog of courses
+    Catalog {
+        #[command(subcommand)]
+        command: CatalogCommand,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum CatalogCommand {
+    /// Import the courses of a path-finder topics export into a catalog, reporting the courses
+    /// added, removed, and skipped for lacking a component
+    ///
+    /// The export can be downloaded (requires authorization) from
+    /// https://path-finder.apps.buffalo.edu/api/cached/topics
+    Import {
+        /// Path-finder topics export in JSON format
+        input: PathBuf,
+        /// Catalog in CSV format to merge into, created if it doesn't exist (e.g. ubs-lib/data/courses.csv)
+        catalog: PathBuf,
+        /// Remove courses from the catalog that aren't in the export
+        #[clap(long)]
+        prune: bool,
+    },
+}
+
 #[derive(Debug, Clone, ValueEnum)]
 pub enum DataFormat {
     Json,